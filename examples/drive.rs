@@ -0,0 +1,22 @@
+use rtplot::{Figure, PlotType};
+use std::f32::consts::PI;
+
+fn main() {
+    let mut figure = Figure::new(1000)
+        .ylim([-1.0, 1.0])
+        .xlabel("Time (s)")
+        .ylabel("Amplitude")
+        .plot_type(PlotType::Line)
+        .color(0x00, 0x80, 0x00);
+
+    // An endless sine wave, ten samples at a time.
+    let mut n = 0;
+    let sine = std::iter::repeat_with(move || {
+        let chunk: Vec<f32> = (n..n + 10)
+            .map(|i| (2.0 * PI * i as f32 / 200.0).sin())
+            .collect();
+        n += 10;
+        chunk
+    });
+    figure.drive(sine);
+}
@@ -1,7 +1,7 @@
 use num::Complex;
 use rand::seq::SliceRandom;
 use rand_distr::{Distribution, Normal};
-use rtplot::{Figure, PlotType};
+use rtplot::{Figure, MarkerShape};
 use std::f32::consts::PI;
 
 fn generate_symbol() -> Complex<f32> {
@@ -23,8 +23,7 @@ fn main() {
     let mut figure = Figure::new(10000)
         .xlim([-1.5, 1.5])
         .ylim([-1.5, 1.5])
-        .plot_type(PlotType::Dot)
-        .color(0x50, 0x20, 0x50);
+        .constellation_style(MarkerShape::Square, 0.005, [0x50, 0x20, 0x50]);
     Figure::display(&mut figure, |fig| {
         let symbol = generate_symbol();
         fig.plot_complex_stream(&[symbol]);
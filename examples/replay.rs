@@ -0,0 +1,15 @@
+use rtplot::{Figure, FileFormat, FileSource, PlotType};
+use std::env;
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .expect("Usage: replay <file of little-endian complex f32 samples>");
+    let source = FileSource::new(path, FileFormat::BinaryComplex, 100)
+        .expect("Could not open the recording")
+        .rate(30.0);
+    let mut figure = Figure::new(1000)
+        .plot_type(PlotType::Dot)
+        .color(0x50, 0x20, 0x50);
+    figure.run_source(source);
+}
@@ -0,0 +1,33 @@
+use num::Complex;
+use rand_distr::{Distribution, Normal};
+use rtplot::{Figure, PlotType};
+use std::f32::consts::PI;
+
+fn main() {
+    let normal = Normal::new(0.0, 0.5).unwrap();
+    let mut rng = rand::thread_rng();
+    let mut figure = Figure::new(1024)
+        .ylim([-80.0, 10.0])
+        .xlabel("Frequency (cycles/sample)")
+        .ylabel("Power (dB)")
+        .plot_type(PlotType::Line)
+        .color(0x00, 0x00, 0xA0)
+        .max_hold_color(0xA0, 0x00, 0x00);
+
+    let mut frame = 0;
+    Figure::display(&mut figure, |fig| {
+        // A tone that only appears every so often, which the max hold catches.
+        let amplitude = if frame % 30 == 0 { 1.0 } else { 0.0 };
+        let samples: Vec<Complex<f32>> = (0..1024)
+            .map(|i| {
+                let phase = 2.0 * PI * 0.1 * i as f32;
+                Complex::new(
+                    amplitude * phase.cos() + normal.sample(&mut rng) as f32,
+                    amplitude * phase.sin() + normal.sample(&mut rng) as f32,
+                )
+            })
+            .collect();
+        fig.plot_spectrum_maxhold(&samples);
+        frame += 1;
+    });
+}
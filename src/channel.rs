@@ -0,0 +1,205 @@
+use crate::figure::Figure;
+use crate::source::{DataSource, PlotData};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, RecvTimeoutError, SendError, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+/// Creates a channel for feeding a figure from async tasks. The sender can
+/// be moved onto an async runtime, while the receiver is passed to
+/// `Figure::run_source` on the main thread. `Figure::run_with_producer`
+/// sets this up for you.
+///
+/// The figure's window and GL context can't be moved off the thread that
+/// created them, and on some platforms that must be the main thread, so
+/// this is the only safe way to combine rtplot with an async runtime: run
+/// the runtime on another thread and send it data through this channel.
+///
+/// At most `capacity` batches wait in the channel at once. Past that,
+/// sending waits for the figure to catch up, which together with
+/// `Eviction::Block` keeps a fast producer from losing data.
+pub fn channel(capacity: usize) -> (PlotSender, PlotReceiver) {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    let waiting = Arc::new(Mutex::new(Vec::new()));
+    (
+        PlotSender {
+            sender,
+            waiting: waiting.clone(),
+        },
+        PlotReceiver {
+            receiver: Some(receiver),
+            waiting,
+        },
+    )
+}
+
+/// The wakers of sends waiting for room in a channel.
+type Waiting = Arc<Mutex<Vec<Waker>>>;
+
+fn wake_all(waiting: &Waiting) {
+    for waker in waiting.lock().unwrap().drain(..) {
+        waker.wake();
+    }
+}
+
+/// The sending half of a `channel`. Can be cloned to feed a figure from
+/// several tasks.
+#[derive(Clone)]
+pub struct PlotSender {
+    sender: mpsc::SyncSender<PlotData>,
+    waiting: Waiting,
+}
+
+impl PlotSender {
+    /// Sends a batch of data to the figure. While the channel is full, the
+    /// task yields to its runtime until the figure makes room, rather than
+    /// blocking the thread. Fails if the receiver has been dropped, such as
+    /// when the window was closed.
+    pub async fn send(&self, data: PlotData) -> Result<(), SendError<PlotData>> {
+        Sending {
+            sender: self,
+            data: Some(data),
+        }
+        .await
+    }
+}
+
+/// The future returned by `PlotSender::send`.
+struct Sending<'a> {
+    sender: &'a PlotSender,
+    data: Option<PlotData>,
+}
+
+impl Future for Sending<'_> {
+    type Output = Result<(), SendError<PlotData>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let data = self.data.take().expect("send polled after completion");
+        // The lock is held across the attempt so the receiver can't make
+        // room between a full channel and the waker being registered.
+        let mut waiting = self.sender.waiting.lock().unwrap();
+        match self.sender.sender.try_send(data) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(TrySendError::Disconnected(data)) => Poll::Ready(Err(SendError(data))),
+            Err(TrySendError::Full(data)) => {
+                waiting.push(cx.waker().clone());
+                drop(waiting);
+                self.data = Some(data);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// The receiving half of a `channel`, used as the data source of a figure.
+pub struct PlotReceiver {
+    receiver: Option<mpsc::Receiver<PlotData>>,
+    waiting: Waiting,
+}
+
+impl DataSource for PlotReceiver {
+    /// Waits briefly for the next batch so the window keeps handling events
+    /// while the senders are idle, returning `PlotData::Redraw` if nothing
+    /// arrives in time. Once every sender has been dropped, the source is
+    /// exhausted.
+    fn next(&mut self) -> Option<PlotData> {
+        let received = self
+            .receiver
+            .as_ref()?
+            .recv_timeout(Duration::from_millis(16));
+        match received {
+            Ok(data) => {
+                wake_all(&self.waiting);
+                Some(data)
+            }
+            Err(RecvTimeoutError::Timeout) => Some(PlotData::Redraw),
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl Drop for PlotReceiver {
+    fn drop(&mut self) {
+        // Disconnect before waking so waiting sends see the channel closed
+        // instead of waiting for room again.
+        self.receiver.take();
+        wake_all(&self.waiting);
+    }
+}
+
+impl Figure<'_> {
+    /// Plots data from an async producer until every sender is dropped or
+    /// the window is closed. The producer is run on a new thread with the
+    /// sending half of a `channel` of the given capacity, while the figure
+    /// keeps the current thread, which should be the one that created it.
+    /// Start the async runtime inside the producer and move the sender into
+    /// its tasks.
+    ///
+    /// Returns the producer's thread. Its sends fail once the window is
+    /// closed, so a producer that stops on errors can then be joined.
+    pub fn run_with_producer<F>(&mut self, capacity: usize, producer: F) -> thread::JoinHandle<()>
+    where
+        F: FnOnce(PlotSender) + Send + 'static,
+    {
+        let (sender, receiver) = channel(capacity);
+        let handle = thread::spawn(move || producer(sender));
+        self.run_source(receiver);
+        handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct Flag(Mutex<bool>);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            *self.0.lock().unwrap() = true;
+        }
+    }
+
+    fn poll_send(send: &mut Pin<Box<impl Future>>, flag: &Arc<Flag>) -> bool {
+        let waker = Waker::from(flag.clone());
+        send.as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_ready()
+    }
+
+    #[test]
+    fn send_waits_for_room_without_blocking() {
+        let (sender, mut receiver) = channel(1);
+        let flag = Arc::new(Flag(Mutex::new(false)));
+        let mut first = Box::pin(sender.send(PlotData::Real(vec![1.0])));
+        assert!(poll_send(&mut first, &flag));
+
+        let mut second = Box::pin(sender.send(PlotData::Real(vec![2.0])));
+        assert!(!poll_send(&mut second, &flag));
+        assert!(!*flag.0.lock().unwrap());
+
+        assert!(matches!(receiver.next(), Some(PlotData::Real(_))));
+        assert!(*flag.0.lock().unwrap());
+        assert!(poll_send(&mut second, &flag));
+    }
+
+    #[test]
+    fn waiting_send_fails_once_the_receiver_is_dropped() {
+        let (sender, receiver) = channel(1);
+        let flag = Arc::new(Flag(Mutex::new(false)));
+        let mut first = Box::pin(sender.send(PlotData::Redraw));
+        assert!(poll_send(&mut first, &flag));
+        let mut second = Box::pin(sender.send(PlotData::Redraw));
+        assert!(!poll_send(&mut second, &flag));
+
+        drop(receiver);
+        assert!(*flag.0.lock().unwrap());
+        let waker = Waker::from(flag.clone());
+        let result = second.as_mut().poll(&mut Context::from_waker(&waker));
+        assert!(matches!(result, Poll::Ready(Err(_))));
+    }
+}
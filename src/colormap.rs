@@ -0,0 +1,251 @@
+/// A color along with its opacity. Converts from `[u8; 3]` as an opaque
+/// color, or from `[u8; 4]` with the opacity last.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Color {
+    /// The red, green and blue channels.
+    pub rgb: [u8; 3],
+
+    /// The opacity, from 0 for fully transparent to 255 for opaque.
+    pub alpha: u8,
+}
+
+impl Color {
+    /// Creates a color from its channels and opacity.
+    pub fn rgba(r: u8, g: u8, b: u8, alpha: u8) -> Self {
+        Color {
+            rgb: [r, g, b],
+            alpha,
+        }
+    }
+
+    /// Returns a color with the given channels and this color's opacity.
+    pub fn with_rgb(self, rgb: [u8; 3]) -> Self {
+        Color { rgb, ..self }
+    }
+}
+
+impl Default for Color {
+    /// Opaque black.
+    fn default() -> Self {
+        Color::from([0, 0, 0])
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from(rgb: [u8; 3]) -> Self {
+        Color { rgb, alpha: 255 }
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([r, g, b, alpha]: [u8; 4]) -> Self {
+        Color::rgba(r, g, b, alpha)
+    }
+}
+
+/// A mapping from a position between 0.0 and 1.0 to a color.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Colormap {
+    /// Dark purple through blue and green to yellow. Perceptually uniform and
+    /// readable in grayscale.
+    #[default]
+    Viridis,
+
+    /// Black to white.
+    Grayscale,
+
+    /// Blue through cyan, green and yellow to red.
+    Jet,
+
+    /// Red through yellow, green, cyan, blue and magenta back to red. The
+    /// ends meet, so it suits cyclic values such as phase.
+    Hsv,
+
+    /// Blue through white to red, for signed values such as correlation or
+    /// error. Sampled with `sample_value`, zero falls on the white center.
+    Diverging,
+}
+
+impl Colormap {
+    /// Returns the colors the colormap interpolates between, evenly spaced
+    /// from 0.0 to 1.0.
+    fn stops(&self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &[
+                [0x44, 0x01, 0x54],
+                [0x3b, 0x52, 0x8b],
+                [0x21, 0x91, 0x8c],
+                [0x5e, 0xc9, 0x62],
+                [0xfd, 0xe7, 0x25],
+            ],
+            Colormap::Grayscale => &[[0x00, 0x00, 0x00], [0xff, 0xff, 0xff]],
+            Colormap::Jet => &[
+                [0x00, 0x00, 0x80],
+                [0x00, 0x00, 0xff],
+                [0x00, 0xff, 0xff],
+                [0xff, 0xff, 0x00],
+                [0xff, 0x00, 0x00],
+                [0x80, 0x00, 0x00],
+            ],
+            Colormap::Hsv => &[
+                [0xff, 0x00, 0x00],
+                [0xff, 0xff, 0x00],
+                [0x00, 0xff, 0x00],
+                [0x00, 0xff, 0xff],
+                [0x00, 0x00, 0xff],
+                [0xff, 0x00, 0xff],
+                [0xff, 0x00, 0x00],
+            ],
+            Colormap::Diverging => &[
+                [0x21, 0x66, 0xac],
+                [0x67, 0xa9, 0xcf],
+                [0xd1, 0xe5, 0xf0],
+                [0xf7, 0xf7, 0xf7],
+                [0xfd, 0xdb, 0xc7],
+                [0xef, 0x8a, 0x62],
+                [0xb2, 0x18, 0x2b],
+            ],
+        }
+    }
+
+    /// Returns whether the colormap is centered on zero rather than running
+    /// from a minimum to a maximum.
+    pub fn is_diverging(&self) -> bool {
+        matches!(self, Colormap::Diverging)
+    }
+
+    /// Returns where a value falls along the colormap, given the range of
+    /// values being colored. Sequential colormaps run from min to max. A
+    /// diverging colormap is centered on zero and spans the range made
+    /// symmetric about it, so zero always lands on the center and values of
+    /// the same size but opposite sign are equally strong. An empty range
+    /// maps everything to the start, or the center if diverging.
+    pub fn position(&self, value: f32, range: [f32; 2]) -> f32 {
+        let [min, max] = range;
+        if self.is_diverging() {
+            let half = min.abs().max(max.abs());
+            if half > 0.0 {
+                0.5 + value / (2.0 * half)
+            } else {
+                0.5
+            }
+        } else if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the color for a value, given the range of values being
+    /// colored, such as to color a scatter plot by a third variable through
+    /// `point_colors`.
+    pub fn sample_value(&self, value: f32, range: [f32; 2]) -> [u8; 3] {
+        self.sample(self.position(value, range))
+    }
+
+    /// Returns the color at the given position along the colormap. Positions
+    /// outside of 0.0 to 1.0 are clamped to the ends of the colormap.
+    pub fn sample(&self, t: f32) -> [u8; 3] {
+        let stops = self.stops();
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let scaled = t * (stops.len() - 1) as f32;
+        let index = (scaled.floor() as usize).min(stops.len() - 2);
+        let frac = scaled - index as f32;
+        let (from, to) = (stops[index], stops[index + 1]);
+        let mut color = [0; 3];
+        for i in 0..3 {
+            color[i] = (from[i] as f32 + frac * (to[i] as f32 - from[i] as f32)).round() as u8;
+        }
+        color
+    }
+}
+
+/// A palette of distinct colors handed out in turn, so each of several
+/// series gets its own color without picking them by hand. After the last
+/// color, the cycle starts again from the first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorCycle {
+    colors: Vec<[u8; 3]>,
+    next: usize,
+}
+
+impl ColorCycle {
+    /// The ten colors of matplotlib's tab10 palette, used by default.
+    pub const TAB10: [[u8; 3]; 10] = [
+        [0x1f, 0x77, 0xb4],
+        [0xff, 0x7f, 0x0e],
+        [0x2c, 0xa0, 0x2c],
+        [0xd6, 0x27, 0x28],
+        [0x94, 0x67, 0xbd],
+        [0x8c, 0x56, 0x4b],
+        [0xe3, 0x77, 0xc2],
+        [0x7f, 0x7f, 0x7f],
+        [0xbc, 0xbd, 0x22],
+        [0x17, 0xbe, 0xcf],
+    ];
+
+    /// Creates a cycle through the given colors. An empty palette falls back
+    /// to tab10.
+    pub fn new(colors: Vec<[u8; 3]>) -> Self {
+        if colors.is_empty() {
+            return Self::default();
+        }
+        ColorCycle { colors, next: 0 }
+    }
+
+    /// Returns the colors in the palette, in the order they're handed out.
+    pub fn colors(&self) -> &[[u8; 3]] {
+        &self.colors
+    }
+
+    /// Returns the next color in the cycle.
+    pub fn next_color(&mut self) -> [u8; 3] {
+        let color = self.colors[self.next];
+        self.next = (self.next + 1) % self.colors.len();
+        color
+    }
+
+    /// Starts the cycle again from the first color.
+    pub fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+impl Default for ColorCycle {
+    fn default() -> Self {
+        ColorCycle {
+            colors: Self::TAB10.to_vec(),
+            next: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diverging_zero_is_centered_for_asymmetric_ranges() {
+        let cmap = Colormap::Diverging;
+        assert_eq!(cmap.position(0.0, [-1.0, 4.0]), 0.5);
+        assert_eq!(cmap.sample_value(0.0, [-1.0, 4.0]), [0xf7, 0xf7, 0xf7]);
+        assert_eq!(cmap.position(4.0, [-1.0, 4.0]), 1.0);
+        assert_eq!(cmap.position(-1.0, [-1.0, 4.0]), 0.375);
+    }
+
+    #[test]
+    fn diverging_opposite_values_are_symmetric() {
+        let cmap = Colormap::Diverging;
+        for value in [0.5, 1.0, 2.5, 4.0] {
+            let above = cmap.position(value, [-1.0, 4.0]);
+            let below = cmap.position(-value, [-1.0, 4.0]);
+            assert!((above - 0.5 - (0.5 - below)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn empty_range_maps_to_the_start_or_center() {
+        assert_eq!(Colormap::Diverging.position(3.0, [0.0, 0.0]), 0.5);
+        assert_eq!(Colormap::Viridis.position(3.0, [2.0, 2.0]), 0.0);
+    }
+}
@@ -1,23 +1,421 @@
+use crate::colormap::{Color, ColorCycle, Colormap};
+use crate::source::{DataSource, PlotData};
+use crate::spectrum::{self, WindowFunction};
 use crate::utils;
-use crate::window::{Vertex, Window};
+use crate::window::{FrameInfo, Overlay, Vertex, Window, DEFAULT_FONT_SIZE};
 use cgmath::Point2;
+use glium::glutin::event::VirtualKeyCode;
 use glium::glutin::platform::desktop::EventLoopExtDesktop;
 use itertools_num::linspace;
 use num::Complex;
 use slice_deque::SliceDeque;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
+#[non_exhaustive]
 pub enum PlotType {
-    /// Draws a continuous line between points.
+    /// Draws a continuous line between points. A single point is drawn as a
+    /// dot, since there is no line to draw.
     Line,
 
-    /// Each point is drawn as a small diamond.
+    /// Each point is drawn as a small marker.
+    #[default]
     Dot,
+
+    /// Draws a continuous line between points with a dot on each point. For
+    /// complex plots, this shows both the symbols and the path between them.
+    LineDot,
+
+    /// Draws a vertical line from the baseline to each point, topped with a
+    /// dot.
+    Stem,
+
+    /// Draws a bar from the baseline to each point.
+    Bar,
+
+    /// Fills the region between the line and the baseline.
+    Area,
+}
+
+impl PlotType {
+    /// Returns every plot type, in the order they're declared. New plot
+    /// types are added here as well, so code cycling through the types picks
+    /// them up without changes.
+    pub fn iter() -> impl Iterator<Item = PlotType> {
+        [
+            PlotType::Line,
+            PlotType::Dot,
+            PlotType::LineDot,
+            PlotType::Stem,
+            PlotType::Bar,
+            PlotType::Area,
+        ]
+        .iter()
+        .copied()
+    }
+}
+
+/// What happens to samples when a streaming plot's queue is full.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Eviction {
+    /// The oldest samples are removed to make room for new ones.
+    #[default]
+    DropOldest,
+
+    /// New samples are discarded, keeping the first samples captured.
+    DropNewest,
+
+    /// New samples aren't accepted while the queue is full. Samples passed
+    /// straight to the stream methods are discarded as with `DropNewest`, but
+    /// `run_source` holds them back and stops pulling from its source until
+    /// there's room, so a producer feeding it through a `channel` waits
+    /// rather than losing data. Make room with `clear_samples`.
+    Block,
+}
+
+/// A corner of the plot area.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Corner {
+    /// The top-left corner.
+    TopLeft,
+
+    /// The top-right corner.
+    #[default]
+    TopRight,
+
+    /// The bottom-left corner.
+    BottomLeft,
+
+    /// The bottom-right corner.
+    BottomRight,
+}
+
+/// The shape of the markers drawn on each point.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum MarkerShape {
+    /// A filled circle.
+    #[default]
+    Circle,
+
+    /// A filled square.
+    Square,
+
+    /// A filled square standing on one corner.
+    Diamond,
+}
+
+/// The shape drawn at the ends of lines.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum LineCap {
+    /// The line stops square at its end point.
+    #[default]
+    Butt,
+
+    /// The line ends in a half circle around its end point.
+    Round,
+
+    /// The line ends in a square extending past its end point.
+    Square,
+}
+
+/// The shape drawn where two segments of a line meet.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum LineJoin {
+    /// The outer edges of the segments are extended until they meet.
+    #[default]
+    Miter,
+
+    /// The corner is rounded off.
+    Round,
+
+    /// The corner is cut off flat.
+    Bevel,
+}
+
+/// Limits shared between an axis of several figures, so zooming one figure
+/// moves the others with it. Clones refer to the same limits.
+#[derive(Clone, Debug, Default)]
+pub struct SharedAxis(Rc<RefCell<SharedLimits>>);
+
+/// The limits behind a `SharedAxis`, counting how many times they've been
+/// set so figures can tell when they've changed.
+#[derive(Debug, Default)]
+struct SharedLimits {
+    lims: Option<[f32; 2]>,
+    generation: u64,
+}
+
+impl SharedAxis {
+    /// Creates a shared axis with no limits yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared limits, if they've been set.
+    pub fn get(&self) -> Option<[f32; 2]> {
+        self.0.borrow().lims
+    }
+
+    /// Sets the shared limits. Linked figures take them on at their next
+    /// draw.
+    pub fn set(&self, lims: [f32; 2]) {
+        let mut shared = self.0.borrow_mut();
+        shared.lims = Some(lims);
+        shared.generation += 1;
+    }
+
+    /// Returns how many times the limits have been set.
+    fn generation(&self) -> u64 {
+        self.0.borrow().generation
+    }
+}
+
+/// What an empty streaming queue starts out holding.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Prefill {
+    /// The queue starts empty, so the trace grows across the plot as it
+    /// fills.
+    #[default]
+    Empty,
+
+    /// The queue starts full of zeros, so the trace spans the plot from the
+    /// first frame.
+    Zeros,
+
+    /// The queue starts full of NaNs, which aren't drawn, so the x axis spans
+    /// the whole queue from the first frame without drawing any fake data.
+    NanGap,
+}
+
+impl Prefill {
+    /// Returns the value to fill the queue with, if any.
+    fn value(&self) -> Option<f32> {
+        match self {
+            Prefill::Empty => None,
+            Prefill::Zeros => Some(0.0),
+            Prefill::NanGap => Some(f32::NAN),
+        }
+    }
+}
+
+/// Something drawn on the plot that can be stacked above or below the
+/// others with `layer_order`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Layer {
+    /// Extra lines drawn alongside the data, such as the reference trace from
+    /// `plot_stream_with_overlay`.
+    Overlays,
+
+    /// The data itself.
+    Data,
+
+    /// The curve drawn by `set_reference_fn`.
+    Reference,
+}
+
+/// How a path added with `add_path` is drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathStyle {
+    /// The outline of the path is drawn as a line as wide as the data.
+    Stroke,
+
+    /// The inside of the path is filled. Open subpaths are treated as
+    /// closed.
+    Fill,
+}
+
+/// How to handle points that fall outside the limits of the plot.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ClipMode {
+    /// Points outside the limits are skipped.
+    #[default]
+    Drop,
+
+    /// Points outside the limits are moved onto the nearest edge of the plot.
+    Clip,
+
+    /// Points outside the limits are drawn as-is, past the edges of the plot.
+    Keep,
+}
+
+/// A condition on incoming samples that freezes the plot once met, set with
+/// `capture_on`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// A sample at or above the level following one below it.
+    Rising(f32),
+
+    /// A sample below the level following one at or above it.
+    Falling(f32),
+
+    /// Any sample above the level.
+    Above(f32),
+
+    /// Any sample below the level.
+    Below(f32),
+}
+
+impl Condition {
+    /// Returns whether a sample meets the condition, given the sample before
+    /// it, if any.
+    fn met(&self, prev: Option<f32>, value: f32) -> bool {
+        match *self {
+            Condition::Rising(level) => prev.is_some_and(|prev| prev < level) && value >= level,
+            Condition::Falling(level) => prev.is_some_and(|prev| prev >= level) && value < level,
+            Condition::Above(level) => value > level,
+            Condition::Below(level) => value < level,
+        }
+    }
+}
+
+/// Draws the plot in one color below a level and another color at or above
+/// it.
+#[derive(Copy, Clone, Debug)]
+pub struct ColorThreshold {
+    /// The level, in data coordinates, where the color changes.
+    pub level: f32,
+
+    /// The color to use below the level.
+    pub below: [u8; 3],
+
+    /// The color to use at or above the level.
+    pub above: [u8; 3],
+}
+
+/// How numbers in tick labels are written.
+#[derive(Copy, Clone, Debug)]
+pub struct NumberFormat {
+    /// The character separating the whole and fractional parts. Defaults to
+    /// a period.
+    pub decimal_separator: char,
+
+    /// If set, the character placed between each group of three digits in
+    /// the whole part, such as a comma or a space. Defaults to None.
+    pub thousands_separator: Option<char>,
+
+    /// Whether to write tick labels in engineering notation, scaling each
+    /// axis by a power of a thousand and marking it with an SI prefix, such
+    /// as 1.5k or 470µ. Defaults to false.
+    pub engineering: bool,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: None,
+            engineering: false,
+        }
+    }
+}
+
+/// SI prefixes for each power of a thousand from 10^-24 to 10^24.
+const SI_PREFIXES: [&str; 17] = [
+    "y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+];
+
+impl NumberFormat {
+    /// Writes the value with the given number of decimal places.
+    pub fn format(&self, value: f32, precision: usize) -> String {
+        let formatted = format!("{:.*}", precision, value);
+        let (sign, formatted) = match formatted.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", formatted.as_str()),
+        };
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted, None),
+        };
+
+        let mut out = String::from(sign);
+        for (i, digit) in whole.chars().enumerate() {
+            if let Some(separator) = self.thousands_separator {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    out.push(separator);
+                }
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(self.decimal_separator);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Writes the ticks along an axis with the given number of decimal
+    /// places. In engineering notation, every tick on the axis shares the SI
+    /// prefix suited to the largest of them, and the number of decimal places
+    /// is instead just enough to tell neighbouring ticks apart once scaled.
+    pub fn format_ticks(&self, ticks: &[f32], precision: usize) -> Vec<String> {
+        if !self.engineering {
+            return ticks
+                .iter()
+                .map(|tick| self.format(*tick, precision))
+                .collect();
+        }
+        let magnitude = ticks
+            .iter()
+            .map(|tick| tick.abs())
+            .filter(|tick| tick.is_finite())
+            .fold(0.0, f32::max);
+        let group = if magnitude > 0.0 {
+            ((magnitude.log10() / 3.0).floor() as i32).clamp(-8, 8)
+        } else {
+            0
+        };
+        let scale = 10f32.powi(3 * group);
+        let precision = match ticks {
+            [first, second, ..] if first != second => {
+                (-((second - first).abs() / scale).log10().floor()).max(0.0) as usize
+            }
+            _ => precision,
+        };
+        let prefix = SI_PREFIXES[(group + 8) as usize];
+        ticks
+            .iter()
+            .map(|tick| format!("{}{}", self.format(tick / scale, precision), prefix))
+            .collect()
+    }
 }
 
-impl Default for PlotType {
+/// The positions of the plot and its labels within the window. The window
+/// spans from -1.0 to 1.0 vertically, with the plot centered on 0.0.
+#[derive(Copy, Clone, Debug)]
+pub struct LayoutConfig {
+    /// Half the width and height of the plot area. Defaults to 0.75.
+    pub plot_extent: f32,
+
+    /// The vertical position of the x label. Defaults to -0.90.
+    pub xlabel_offset: f32,
+
+    /// The horizontal position of the y label. Defaults to -0.90.
+    pub ylabel_offset: f32,
+
+    /// The vertical position of the x tick labels. Defaults to -0.80.
+    pub xtick_offset: f32,
+
+    /// The horizontal position of the y tick labels. Defaults to -0.85.
+    pub ytick_offset: f32,
+}
+
+impl Default for LayoutConfig {
     fn default() -> Self {
-        PlotType::Dot
+        Self {
+            plot_extent: 0.75,
+            xlabel_offset: -0.90,
+            ylabel_offset: -0.90,
+            xtick_offset: -0.80,
+            ytick_offset: -0.85,
+        }
     }
 }
 
@@ -32,17 +430,277 @@ pub struct FigureConfig<'a> {
     pub ylim: Option<[f32; 2]>,
 
     /// A label for the x-axis. Defaults to None.
-    pub xlabel: Option<&'a str>,
+    pub xlabel: Option<Cow<'a, str>>,
 
     /// A label for the y-axis. Defaults to None.
-    pub ylabel: Option<&'a str>,
+    pub ylabel: Option<Cow<'a, str>>,
 
-    /// The color of points or lines to be drawn onto the graph. Defaults to
-    /// 0x000000, or black.
-    pub color: [u8; 3],
+    /// The color of points or lines to be drawn onto the graph, along with
+    /// their opacity. Translucent data is blended over whatever is beneath
+    /// it. Defaults to opaque black.
+    pub color: Color,
 
     /// The type of plot to draw. Defaults to a dot plot.
     pub plot_type: PlotType,
+
+    /// The y value, in data coordinates, that Stem, Bar and Area plots are
+    /// drawn from. Defaults to 0.0.
+    pub baseline: f32,
+
+    /// Whether to overlay the time taken to draw the last frame. Defaults to
+    /// false.
+    pub show_fps: bool,
+
+    /// Colors the plot based on whether it is above or below a level. If
+    /// set, this overrides `color`. Defaults to None.
+    pub color_threshold: Option<ColorThreshold>,
+
+    /// Whether to keep the plot square and centered within the window, no
+    /// matter the shape of the window. The grid and data are drawn into a
+    /// centered square viewport, leaving blank margins along the longer
+    /// side. Defaults to false.
+    pub square_plot: bool,
+
+    /// The approximate number of ticks to place along the x axis at nice,
+    /// round values. If set to None, the x axis is divided into six evenly
+    /// spaced ticks. Defaults to None.
+    pub x_tick_target: Option<usize>,
+
+    /// The approximate number of ticks to place along the y axis at nice,
+    /// round values. If set to None, the y axis is divided into five evenly
+    /// spaced ticks. Defaults to None.
+    pub y_tick_target: Option<usize>,
+
+    /// Values along the y axis to place ticks and gridlines at, in place of
+    /// the usual ticks. Ticks outside the limits aren't drawn. Defaults to
+    /// None.
+    pub y_ticks: Option<Vec<f32>>,
+
+    /// The distance between gridlines along the x and y axes, in data units.
+    /// Gridlines and ticks fall on every multiple of the spacing within the
+    /// limits, in place of the automatic ticks, though explicit `y_ticks`
+    /// take priority. An axis with a spacing that isn't positive, or so fine
+    /// that the gridlines would crowd together, keeps its automatic ticks.
+    /// Defaults to None.
+    pub grid_spacing: Option<[f32; 2]>,
+
+    /// Whether to draw emphasized lines where x and y are zero. Defaults to
+    /// false.
+    pub show_zero_axis: bool,
+
+    /// If set, Line plots also draw a marker on every Nth point. Defaults to
+    /// None.
+    pub marker_every: Option<usize>,
+
+    /// How to handle points that fall outside the limits of the plot.
+    /// Defaults to dropping them.
+    pub clip_mode: ClipMode,
+
+    /// Labels to place along the x axis in place of numeric ticks, one per
+    /// bar, evenly dividing the axis. Defaults to None.
+    pub categories: Option<Vec<&'a str>>,
+
+    /// Faint text drawn in the bottom-right corner of the window, such as an
+    /// attribution. Defaults to None.
+    pub watermark: Option<&'a str>,
+
+    /// Whether complex plots autoscale their real and imaginary axes
+    /// independently. By default, both axes share the same limits so the IQ
+    /// plane isn't distorted. Has no effect if either limit is set. Defaults
+    /// to false.
+    pub independent_complex_axes: bool,
+
+    /// Which samples to discard, or whether to hold new ones back, when a
+    /// streaming plot's queue is full. Defaults to dropping the oldest
+    /// samples.
+    pub eviction: Eviction,
+
+    /// A condition on the real samples passed to `plot_stream` and
+    /// `plot_stream_batch` that pauses the plot once met, freezing it on the
+    /// samples that met it. Each capture disarms the condition until it's
+    /// rearmed with `rearm` or the R key. Defaults to None.
+    pub capture: Option<Condition>,
+
+    /// Whether Line and Dot plots are drawn directly as GL lines and points
+    /// instead of being tessellated. This is much faster for large numbers of
+    /// points, but lines are a single pixel wide and dots are small squares.
+    /// Defaults to false.
+    pub fast_render: bool,
+
+    /// The color of the held maximum drawn by `plot_spectrum_maxhold`.
+    /// Defaults to 0x000000, or black.
+    pub max_hold_color: [u8; 3],
+
+    /// The window applied to samples before taking their spectrum in
+    /// `plot_spectrum` and `plot_spectrum_maxhold`. Defaults to
+    /// `WindowFunction::Rectangular`, which leaves the samples as-is.
+    pub spectrum_window: WindowFunction,
+
+    /// The positions of the plot and its labels within the window.
+    pub layout: LayoutConfig,
+
+    /// Radii, in data coordinates, of circles centered on the origin to draw
+    /// behind the data, each labeled with its radius. Useful for judging
+    /// amplitude in IQ plots. Defaults to no circles.
+    pub reference_circles: Vec<f32>,
+
+    /// The palette `plot_many` colors series from when they have no color of
+    /// their own. Each call starts from the first color, so a series keeps
+    /// its color from frame to frame. Defaults to tab10.
+    pub color_cycle: ColorCycle,
+
+    /// The size in pixels the font for labels is rasterized at. Larger sizes
+    /// keep text crisp when exported at a high resolution or shown on hi-DPI
+    /// displays, while smaller ones save memory and startup time. Text is
+    /// laid out the same whatever the size. Defaults to None, which
+    /// rasterizes at 70 pixels.
+    pub font_resolution: Option<u32>,
+
+    /// Characters to include in the font beyond the first 255 of Unicode,
+    /// such as Greek letters for units. Characters missing from the font
+    /// aren't drawn. Defaults to no extra characters.
+    pub extra_glyphs: Vec<char>,
+
+    /// How much autoscaled limits hold on to their previous values, from 0.0
+    /// to 1.0. Each frame, the new limits are blended with the previous ones
+    /// by this factor, so the axes drift gradually instead of jumping every
+    /// frame. Data may briefly fall outside the limits while they catch up.
+    /// Defaults to 0.0, which applies no smoothing.
+    pub limit_smoothing: f32,
+
+    /// Colors the plot along a colormap from the first point to the last,
+    /// showing the order the points were drawn in. If set, this overrides
+    /// `color` and `color_threshold`. Defaults to None.
+    pub colormap: Option<Colormap>,
+
+    /// The number of bins along the value axis of `plot_density_stream`.
+    /// Defaults to None, which uses 64 bins.
+    pub density_rows: Option<usize>,
+
+    /// A color for each point, in the order they're drawn. Lines take the
+    /// color of the first point of each segment. Points past the end of the
+    /// list fall back to the other coloring options. If set, this overrides
+    /// `color`, `colormap` and `color_threshold`. Defaults to None.
+    pub point_colors: Option<Vec<[u8; 3]>>,
+
+    /// Whether to keep the grid and labels in a cached image between frames,
+    /// only redrawing them when the limits or window size change. This saves
+    /// work on plots with fixed limits, but the cached grid isn't
+    /// multisampled. Defaults to false.
+    pub cache_grid: bool,
+
+    /// Whether to keep the mesh of the last frame so it can be presented
+    /// again when the window is uncovered, regains focus or is asked to
+    /// redraw by the window system, rather than showing stale or blank
+    /// content until the next plot call. The vertices and overlays of the
+    /// last draw are kept too, for `Figure::redraw` and `Figure::save_svg`.
+    /// Costs the memory of one frame's mesh and a copy of the vertices each
+    /// draw. Defaults to false.
+    pub keep_last_draw: bool,
+
+    /// How closely tessellated lines and shapes follow their true outlines,
+    /// in plot coordinates. Lower values give smoother curves at the cost of
+    /// more vertices. Defaults to None, which uses a tolerance of 0.01.
+    pub tessellation_tolerance: Option<f32>,
+
+    /// How closely dots and other markers follow a true circle, in plot
+    /// coordinates, separately from lines and other shapes. Lower values
+    /// give rounder markers at the cost of more vertices. Defaults to None,
+    /// which uses the tessellation tolerance.
+    pub marker_tolerance: Option<f32>,
+
+    /// Whether to turn off depth testing and layer everything purely in the
+    /// order it's drawn, with the data over the grid. This can avoid
+    /// artifacts on drivers with poor depth buffer precision. Defaults to
+    /// false.
+    pub disable_depth_test: bool,
+
+    /// The shape drawn at the ends of lines. Defaults to butt caps.
+    pub line_cap: LineCap,
+
+    /// The shape drawn where two segments of a line meet. Defaults to miter
+    /// joins.
+    pub line_join: LineJoin,
+
+    /// If set, `plot_stream` shows the newest samples at this many samples
+    /// per pixel across the plot, scrolling as new samples arrive, instead
+    /// of fitting the whole queue into the plot. The queue should hold at
+    /// least as many samples as fit across the plot. Defaults to None.
+    pub samples_per_pixel: Option<f32>,
+
+    /// How numbers in tick labels are written. Defaults to a period decimal
+    /// separator with no grouping of thousands.
+    pub number_format: NumberFormat,
+
+    /// The shape of the markers drawn on each point by Dot, LineDot and Stem
+    /// plots. Defaults to a circle.
+    pub marker_shape: MarkerShape,
+
+    /// The radius of markers, or half the width for square markers, in plot
+    /// coordinates. The plot spans 1.5 across by default. Defaults to None,
+    /// which uses 0.01.
+    pub marker_size: Option<f32>,
+
+    /// The color of the reference curve drawn by `plot_stream_with_overlay`.
+    /// Defaults to 0x000000, or black.
+    pub overlay_color: [u8; 3],
+
+    /// The color of the curve drawn by `set_reference_fn`. Defaults to
+    /// 0x000000, or black.
+    pub reference_color: [u8; 3],
+
+    /// The order to stack the layers of the plot in, from bottom to top.
+    /// Layers left out aren't drawn. This doesn't apply to data drawn
+    /// directly by `fast_render`. Defaults to None, which draws the overlays,
+    /// then the data, then the reference curve.
+    pub layer_order: Option<Vec<Layer>>,
+
+    /// If set, the approximate distance in pixels between ticks. Ticks are
+    /// placed at nice, round values, with their number adapting to the size
+    /// of the plot on screen so the grid stays equally dense as the window
+    /// is resized or the limits change. Overrides the tick targets. Defaults
+    /// to None.
+    pub tick_spacing: Option<f32>,
+
+    /// Whether to show the min, max, mean and RMS of the real data being
+    /// plotted in a corner of the plot. Defaults to false.
+    pub show_stats: bool,
+
+    /// The corner of the plot to show the statistics in. Defaults to the top
+    /// right.
+    pub stats_corner: Corner,
+
+    /// The color of the Q trace drawn by `plot_complex_iq_time`. The I trace
+    /// is drawn in `color`. Defaults to 0x000000, or black.
+    pub q_color: [u8; 3],
+
+    /// What an empty queue of real samples starts out holding. Only applies
+    /// when evicting the oldest samples, since otherwise the prefill would
+    /// never make room for real samples. Defaults to an empty queue.
+    pub prefill: Prefill,
+
+    /// The most data points to map onto the plot in one draw. This limits
+    /// the input to tessellation rather than the mesh itself: each point
+    /// still becomes as many vertices as the plot type needs, and overlays
+    /// aren't counted. Points past the budget are decimated evenly across
+    /// the data rather than cut off the end. Defaults to None, which draws
+    /// every point.
+    pub max_points: Option<usize>,
+
+    /// Whether `plot_y`, `plot_xy`, `plot_tx_y` and `plot_complex` add their
+    /// points to those from earlier calls instead of replacing them. The
+    /// points kept are capped at the queue size, dropping points according
+    /// to `eviction` as with the streaming plots, and with a queue size of
+    /// zero nothing is kept. `plot_y` continues the sample index on from the
+    /// last point kept. Defaults to false.
+    pub accumulate: bool,
+
+    /// Whether to shade the points by how recent they are, from the newest
+    /// in full color to the oldest faded most of the way into the
+    /// background, showing which way a scrolling plot is moving. This shades
+    /// `color`, or the colors from `color_threshold`, and is overridden by
+    /// `colormap` and `point_colors`. Defaults to false.
+    pub recency_shading: bool,
 }
 
 #[derive(Default)]
@@ -59,7 +717,12 @@ pub struct Figure<'a> {
     /// A queue holding complex samples as above.
     complex_samples: SliceDeque<Complex<f32>>,
 
-    /// The number of points. Defaults to 0.
+    /// A queue holding points kept by the non-streaming plots when
+    /// `accumulate` is set.
+    accumulated: SliceDeque<Point2<f32>>,
+
+    /// The number of points. Defaults to 0, in which case the figure can't
+    /// stream and the streaming plot calls do nothing.
     queue_size: usize,
 
     /// Indicates whether the x axis is dynamic.
@@ -67,38 +730,170 @@ pub struct Figure<'a> {
 
     /// Indicates whether the y axis is dynamic.
     y_dynamic: bool,
+
+    /// The highest power seen in each frequency bin by
+    /// `plot_spectrum_maxhold`.
+    max_hold: Vec<f32>,
+
+    /// The window function and its coefficients for the number of samples
+    /// last passed to the spectrum plots.
+    window_coefficients: Option<(WindowFunction, Vec<f32>)>,
+
+    /// Whether streaming plots are frozen, ignoring new samples so the queue
+    /// can be inspected.
+    paused: bool,
+
+    /// Whether the capture condition is waiting to be met.
+    armed: bool,
+
+    /// The density built up by `plot_density_stream` so far.
+    density: Option<DensityGrid>,
+
+    /// The index of the first sample shown while paused.
+    view_offset: usize,
+
+    /// The number of samples shown while paused. If set to None, the whole
+    /// queue is shown.
+    view_len: Option<usize>,
+
+    /// The number of points that fell outside the limits in the last draw.
+    clipped: usize,
+
+    /// The number of points mapped onto the plot in the last draw.
+    vertex_count: usize,
+
+    /// Whether the last draw was decimated to fit within `max_points`.
+    decimated: bool,
+
+    /// The points mapped onto the plot in the last draw, in data
+    /// coordinates.
+    last_points: Vec<Point2<f32>>,
+
+    /// Points and vertices kept between calls to the plot methods so their
+    /// allocations can be reused rather than made afresh every call.
+    scratch_points: Vec<Point2<f32>>,
+    scratch_vertices: Vec<Vertex>,
+
+    /// The last known position of the mouse, in normalized device
+    /// coordinates.
+    cursor: Option<[f32; 2]>,
+
+    /// Where the mouse was pressed to start selecting a region to zoom to, in
+    /// normalized device coordinates.
+    drag_start: Option<[f32; 2]>,
+
+    /// The x and y limits of the last draw.
+    visible: Option<([f32; 2], [f32; 2])>,
+
+    /// The shared axes the x and y axes are linked to, along with the
+    /// generation of the shared limits last seen.
+    x_link: Option<(SharedAxis, u64)>,
+    y_link: Option<(SharedAxis, u64)>,
+
+    /// Whether the limits are still the seed from `initial_limits`, waiting
+    /// for enough data to autoscale to.
+    seeded: bool,
+}
+
+/// How often samples from `plot_density_stream` have landed in each cell of
+/// a grid of sample index against value.
+struct DensityGrid {
+    /// The number of samples in each frame, one column per sample.
+    columns: usize,
+
+    /// The number of bins along the value axis.
+    rows: usize,
+
+    /// The range of values the rows cover, fixed when the grid is created.
+    range: [f32; 2],
+
+    /// The count for each cell, a row at a time from the lowest values up.
+    counts: Vec<u32>,
+}
+
+/// Input from the window that the figure responds to.
+enum Input {
+    Key(VirtualKeyCode),
+    CursorMoved(f64, f64),
+    MousePressed,
+    MouseReleased,
+    Redraw,
 }
 
 impl<'a> Figure<'a> {
-    /// Create a figure with default settings.
+    /// Create a figure with default settings. The queue size is how many
+    /// samples streaming plots keep. With a queue size of zero, there's
+    /// nowhere to keep samples, so the streaming plot calls do nothing
+    /// rather than drawing an empty or degenerate plot.
     pub fn new(queue_size: usize) -> Self {
-        Self {
-            window: Window::new(),
-            config: FigureConfig::default(),
-            samples: SliceDeque::new(),
-            complex_samples: SliceDeque::new(),
-            queue_size,
-            x_dynamic: true,
-            y_dynamic: true,
-        }
+        Self::from_window(Window::new(), FigureConfig::default(), queue_size)
     }
 
     /// Create a figure from an existing configuration. Useful if you don't
     /// want to use the builder pattern to initialize a figure from scratch.
     pub fn new_with_config(config: FigureConfig<'a>, queue_size: usize) -> Self {
+        Self::from_window(Window::new(), config, queue_size)
+    }
+
+    /// Create a figure that draws into an existing glium display, such as a
+    /// window belonging to a larger application, instead of opening its own.
+    /// The caller keeps ownership of the event loop and handles the window's
+    /// events, so `should_close_window` always returns false and the
+    /// keyboard controls aren't available.
+    pub fn with_display(display: glium::Display, queue_size: usize) -> Self {
+        Self::from_window(
+            Window::with_display(display),
+            FigureConfig::default(),
+            queue_size,
+        )
+    }
+
+    fn from_window(mut window: Window<'a>, config: FigureConfig<'a>, queue_size: usize) -> Self {
+        if config.font_resolution.is_some() || !config.extra_glyphs.is_empty() {
+            window.set_font(Self::font_size(&config), &config.extra_glyphs);
+        }
         let x_dynamic = config.xlim.is_none();
         let y_dynamic = config.ylim.is_none();
+        let armed = config.capture.is_some();
         Self {
-            window: Window::new(),
+            window,
             config,
             samples: SliceDeque::new(),
             complex_samples: SliceDeque::new(),
+            accumulated: SliceDeque::new(),
             queue_size,
             x_dynamic,
             y_dynamic,
+            max_hold: vec![],
+            window_coefficients: None,
+            paused: false,
+            armed,
+            density: None,
+            view_offset: 0,
+            view_len: None,
+            clipped: 0,
+            vertex_count: 0,
+            decimated: false,
+            last_points: vec![],
+            scratch_points: vec![],
+            scratch_vertices: vec![],
+            cursor: None,
+            drag_start: None,
+            seeded: false,
+            visible: None,
+            x_link: None,
+            y_link: None,
         }
     }
 
+    /// Moves the window to the given position on the screen, in logical
+    /// pixels from the top-left corner. By default, the operating system
+    /// decides where the window is placed.
+    pub fn window_position(self, x: f64, y: f64) -> Self {
+        self.window.set_position(x, y);
+        self
+    }
+
     /// Sets the x min and max limits for plotting.
     pub fn xlim(mut self, xlim: [f32; 2]) -> Self {
         self.config.xlim = Some(xlim);
@@ -113,197 +908,1841 @@ impl<'a> Figure<'a> {
         self
     }
 
-    /// Sets the x label to display.
-    pub fn xlabel(mut self, xlabel: &'a str) -> Self {
-        self.config.xlabel = Some(xlabel);
+    /// Sets whether the x axis is autoscaled to fit the data. Turning
+    /// autoscaling on clears any x limits. Turning it off keeps the current
+    /// x limits, or fits them to the first data drawn if there are none.
+    pub fn autoscale_x(mut self, autoscale: bool) -> Self {
+        self.set_autoscale_x(autoscale);
         self
     }
 
-    /// Sets the y label to display.
-    pub fn ylabel(mut self, ylabel: &'a str) -> Self {
-        self.config.ylabel = Some(ylabel);
+    /// Sets whether the y axis is autoscaled to fit the data, as with
+    /// `autoscale_x`.
+    pub fn autoscale_y(mut self, autoscale: bool) -> Self {
+        self.set_autoscale_y(autoscale);
         self
     }
 
-    /// Sets the color of the line to draw.
-    pub fn color(mut self, r: u8, g: u8, b: u8) -> Self {
-        self.config.color = [r, g, b];
+    /// Sets the number of bins along the value axis of
+    /// `plot_density_stream`.
+    pub fn density_rows(mut self, rows: usize) -> Self {
+        self.config.density_rows = Some(rows);
         self
     }
 
-    /// Sets the type of plot to generate.
-    pub fn plot_type(mut self, plot_type: PlotType) -> Self {
-        self.config.plot_type = plot_type;
+    /// Sets the palette `plot_many` colors series from.
+    pub fn color_cycle(mut self, color_cycle: ColorCycle) -> Self {
+        self.config.color_cycle = color_cycle;
         self
     }
 
-    /// Checks events to see if the figure should close or not. Returns
-    /// true if the window received a close event, false otherwise. In
-    /// most cases, you don't need to handle events yourself; use
-    /// Figure::display() instead.
-    pub fn should_close_window(&mut self) -> bool {
-        let mut should_close_window = false;
-
-        let events_loop = &mut self.window.events_loop;
+    /// Sets the size in pixels the font for labels is rasterized at.
+    pub fn font_resolution(mut self, size: u32) -> Self {
+        self.config.font_resolution = Some(size);
+        self.window
+            .set_font(Self::font_size(&self.config), &self.config.extra_glyphs);
+        self
+    }
 
-        events_loop.run_return(|event, _, control_flow| {
-            use glium::glutin::event::{Event, WindowEvent};
-            use glium::glutin::event_loop::ControlFlow;
-            #[allow(clippy::single_match)]
-            match event {
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::Destroyed | WindowEvent::CloseRequested => {
-                        should_close_window = true
-                    }
-                    _ => (),
-                },
-                _ => (),
-            }
-            *control_flow = ControlFlow::Exit;
-        });
-        should_close_window
+    /// Adds characters beyond the first 255 of Unicode to the font for
+    /// labels.
+    pub fn extra_glyphs(mut self, glyphs: &[char]) -> Self {
+        self.config.extra_glyphs = glyphs.to_vec();
+        self.window
+            .set_font(Self::font_size(&self.config), &self.config.extra_glyphs);
+        self
     }
 
-    /// Normalizes the received points to [-0.5, 0.5] for drawing in OpenGL.
-    fn normalize(&mut self, points: &[Point2<f32>]) -> Vec<Vertex> {
-        let [min_x, max_x] = if self.x_dynamic {
-            let xlims = utils::calc_xlims(points);
-            self.config.xlim = Some(xlims);
-            xlims
-        } else {
-            self.config.xlim.unwrap()
-        };
-        let [min_y, max_y] = if self.y_dynamic {
-            let ylims = utils::calc_ylims(points);
-            self.config.ylim = Some(ylims);
-            ylims
-        } else {
-            self.config.ylim.unwrap()
-        };
-        let mut vertices = vec![];
-        for point in points {
-            // If there are points outside the min and max range, skip over
-            // them since we won't draw them anyways.
-            if point.x > max_x || point.x < min_x || point.y > max_y || point.y < min_y {
-                continue;
-            }
-            let error: f32 = 0.0;
-            let x = if (max_x - min_x).abs() > error {
-                1.5 * (point.x - min_x) / (max_x - min_x) - 0.75
-            } else {
-                1.5 * point.x - 0.75
-            };
-            let y = if (max_y - min_y).abs() > error {
-                1.5 * (point.y - min_y) / (max_y - min_y) - 0.75
-            } else {
-                1.5 * point.y - 0.75
-            };
-            vertices.push(Vertex::new(x, y, self.config.color));
-        }
-        vertices
+    /// Returns the size in pixels to rasterize the font at.
+    fn font_size(config: &FigureConfig) -> u32 {
+        config.font_resolution.unwrap_or(DEFAULT_FONT_SIZE)
     }
 
-    /// A helper function for normalizing and drawing points to the window.
-    fn plot(&mut self, points: &[Point2<f32>]) {
-        let vertices = self.normalize(&points);
-        self.window.draw(&vertices, &self.config);
+    /// Sets the x label to display. Takes either a borrowed or an owned
+    /// string.
+    pub fn xlabel(mut self, xlabel: impl Into<Cow<'a, str>>) -> Self {
+        self.config.xlabel = Some(xlabel.into());
+        self
     }
 
-    /// Take an array of 2D points and draw them to the plot. This overrides
-    /// any samples in the queue.
-    pub fn plot_xy<T>(&mut self, points: &[(T, T)])
-    where
-        T: Into<f32> + Copy,
-    {
-        let points: Vec<Point2<f32>> = points
-            .iter()
-            .map(|pt| Point2::new(pt.0.into(), pt.1.into()))
-            .collect();
-        self.plot(&points);
+    /// Sets the y label to display. Takes either a borrowed or an owned
+    /// string.
+    pub fn ylabel(mut self, ylabel: impl Into<Cow<'a, str>>) -> Self {
+        self.config.ylabel = Some(ylabel.into());
+        self
+    }
+
+    /// Sets whether complex plots autoscale their real and imaginary axes
+    /// independently, rather than sharing the same limits.
+    pub fn independent_complex_axes(mut self, independent: bool) -> Self {
+        self.config.independent_complex_axes = independent;
+        self
+    }
+
+    /// Sets faint text to draw in the bottom-right corner of the window.
+    pub fn watermark(mut self, watermark: &'a str) -> Self {
+        self.config.watermark = Some(watermark);
+        self
+    }
+
+    /// Draws circles centered on the origin at each of the given radii, in
+    /// data coordinates, behind the data.
+    pub fn reference_circles(mut self, radii: Vec<f32>) -> Self {
+        self.config.reference_circles = radii;
+        self
+    }
+
+    /// Sets how much autoscaled limits hold on to their previous values, from
+    /// 0.0 for no smoothing up to 1.0.
+    pub fn limit_smoothing(mut self, smoothing: f32) -> Self {
+        self.config.limit_smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets whether the grid and labels are cached between frames and only
+    /// redrawn when the limits or window size change.
+    pub fn cache_grid(mut self, cache_grid: bool) -> Self {
+        self.config.cache_grid = cache_grid;
+        self
+    }
+
+    /// Sets whether the last frame is kept so it can be presented again when
+    /// the window is uncovered or regains focus.
+    pub fn keep_last_draw(mut self, keep_last_draw: bool) -> Self {
+        self.config.keep_last_draw = keep_last_draw;
+        self
+    }
+
+    /// Sets how closely tessellated lines and shapes follow their true
+    /// outlines. Lower values are smoother but slower to draw.
+    pub fn tessellation_tolerance(mut self, tolerance: f32) -> Self {
+        self.config.tessellation_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Sets whether the non-streaming plots add to the points from earlier
+    /// calls instead of replacing them.
+    pub fn accumulate(mut self, accumulate: bool) -> Self {
+        self.config.accumulate = accumulate;
+        self
+    }
+
+    /// Sets whether to fade older points into the background, with the
+    /// newest in full color.
+    pub fn recency_shading(mut self, recency_shading: bool) -> Self {
+        self.config.recency_shading = recency_shading;
+        self
+    }
+
+    /// Sets the most data points to map onto the plot in one draw,
+    /// decimating evenly past that.
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.config.max_points = Some(max_points);
+        self
+    }
+
+    /// Sets how closely markers follow a true circle, separately from the
+    /// tolerance for lines and other shapes.
+    pub fn marker_tolerance(mut self, tolerance: f32) -> Self {
+        self.config.marker_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Sets the shapes drawn at the ends of lines and where their segments
+    /// meet.
+    pub fn line_style(mut self, cap: LineCap, join: LineJoin) -> Self {
+        self.config.line_cap = cap;
+        self.config.line_join = join;
+        self
+    }
+
+    /// Shows streamed samples at a fixed number of samples per pixel,
+    /// scrolling as new samples arrive.
+    pub fn samples_per_pixel(mut self, samples_per_pixel: f32) -> Self {
+        self.config.samples_per_pixel = Some(samples_per_pixel);
+        self
+    }
+
+    /// Sets how numbers in tick labels are written, such as to use a comma as
+    /// the decimal separator.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.config.number_format = number_format;
+        self
+    }
+
+    /// Sets the shape and size of the markers drawn on each point.
+    pub fn marker(mut self, shape: MarkerShape, size: f32) -> Self {
+        self.config.marker_shape = shape;
+        self.config.marker_size = Some(size);
+        self
+    }
+
+    /// Sets up a clean scatter for constellation plots: a dot plot with
+    /// markers of the given shape, size and color.
+    pub fn constellation_style(self, marker: MarkerShape, size: f32, color: [u8; 3]) -> Self {
+        self.plot_type(PlotType::Dot)
+            .marker(marker, size)
+            .color(color[0], color[1], color[2])
+    }
+
+    /// Sets the color of the reference curve drawn by
+    /// `plot_stream_with_overlay`.
+    pub fn overlay_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.config.overlay_color = [r, g, b];
+        self
+    }
+
+    /// Sets the order to stack the layers of the plot in, from bottom to top.
+    pub fn layer_order(mut self, order: Vec<Layer>) -> Self {
+        self.config.layer_order = Some(order);
+        self
+    }
+
+    /// Sets the color of the curve drawn by `set_reference_fn`.
+    pub fn reference_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.config.reference_color = [r, g, b];
+        self
+    }
+
+    /// Places ticks at nice, round values about `pixels` apart on screen,
+    /// adapting their number to the size of the plot.
+    pub fn tick_spacing(mut self, pixels: f32) -> Self {
+        self.config.tick_spacing = Some(pixels);
+        self
+    }
+
+    /// Shows the min, max, mean and RMS of the real data being plotted in the
+    /// given corner of the plot.
+    pub fn show_stats(mut self, corner: Corner) -> Self {
+        self.config.show_stats = true;
+        self.config.stats_corner = corner;
+        self
+    }
+
+    /// Sets the color of the Q trace drawn by `plot_complex_iq_time`.
+    pub fn q_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.config.q_color = [r, g, b];
+        self
+    }
+
+    /// Sets what an empty queue of real samples starts out holding.
+    pub fn prefill(mut self, prefill: Prefill) -> Self {
+        self.config.prefill = prefill;
+        self
+    }
+
+    /// Sets whether depth testing is turned off, layering everything in the
+    /// order it's drawn instead.
+    pub fn disable_depth_test(mut self, disable: bool) -> Self {
+        self.config.disable_depth_test = disable;
+        self
+    }
+
+    /// Sets the color of the line to draw.
+    pub fn color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.config.color = Color::from([r, g, b]);
+        self
+    }
+
+    /// Sets the color of the line to draw along with its opacity, so
+    /// overlapping points and fills show through each other.
+    pub fn rgba(mut self, r: u8, g: u8, b: u8, a: u8) -> Self {
+        self.config.color = Color::rgba(r, g, b, a);
+        self
+    }
+
+    /// Places approximately `x` and `y` ticks along the x and y axes at nice,
+    /// round values.
+    pub fn tick_targets(mut self, x: usize, y: usize) -> Self {
+        self.config.x_tick_target = Some(x);
+        self.config.y_tick_target = Some(y);
+        self
+    }
+
+    /// Seeds the limits to show until a draw has at least a full queue's worth
+    /// of points, after which dynamic axes autoscale as usual. This gives a
+    /// stable view while a stream is starting up without fixing the axes for
+    /// good. Axes with autoscaling turned off keep these limits.
+    pub fn initial_limits(mut self, xlim: [f32; 2], ylim: [f32; 2]) -> Self {
+        self.config.xlim = Some(xlim);
+        self.config.ylim = Some(ylim);
+        self.seeded = true;
+        self
+    }
+
+    /// Places gridlines and ticks at every multiple of `dx` along the x axis
+    /// and `dy` along the y axis, such as every 50 Hz or every 0.1 V. A
+    /// spacing of zero keeps the automatic ticks along that axis.
+    pub fn grid_spacing(mut self, dx: f32, dy: f32) -> Self {
+        self.config.grid_spacing = Some([dx, dy]);
+        self
+    }
+
+    /// Places the y axis ticks and gridlines at the given values instead of
+    /// spacing them out automatically.
+    pub fn y_ticks(mut self, ticks: Vec<f32>) -> Self {
+        self.config.y_ticks = Some(ticks);
+        self
+    }
+
+    /// Sets whether Line and Dot plots are drawn directly as GL lines and
+    /// points instead of being tessellated.
+    pub fn fast_render(mut self, fast_render: bool) -> Self {
+        self.config.fast_render = fast_render;
+        self
+    }
+
+    /// Arms a single-shot capture, pausing the plot on the first samples that
+    /// meet the condition, like a triggered oscilloscope. Press R or call
+    /// `rearm` to resume and wait for the next capture.
+    pub fn capture_on(mut self, condition: Condition) -> Self {
+        self.config.capture = Some(condition);
+        self.armed = true;
+        self
+    }
+
+    /// Sets which samples to discard, or whether to hold new ones back, when
+    /// a streaming plot's queue is full.
+    pub fn eviction(mut self, eviction: Eviction) -> Self {
+        self.config.eviction = eviction;
+        self
+    }
+
+    /// Sets how to handle points that fall outside the limits of the plot.
+    pub fn clip_mode(mut self, clip_mode: ClipMode) -> Self {
+        self.config.clip_mode = clip_mode;
+        self
+    }
+
+    /// Draws a marker on every Nth point of a Line plot.
+    pub fn marker_every(mut self, every: usize) -> Self {
+        self.config.marker_every = Some(every);
+        self
+    }
+
+    /// Sets whether to draw emphasized lines where x and y are zero.
+    pub fn show_zero_axis(mut self, show_zero_axis: bool) -> Self {
+        self.config.show_zero_axis = show_zero_axis;
+        self
+    }
+
+    /// Sets the positions of the plot and its labels within the window.
+    pub fn layout(mut self, layout: LayoutConfig) -> Self {
+        self.config.layout = layout;
+        self
+    }
+
+    /// Sets whether to keep the plot square and centered within the window.
+    pub fn square_plot(mut self, square_plot: bool) -> Self {
+        self.config.square_plot = square_plot;
+        self
+    }
+
+    /// Sets the window applied to samples before taking their spectrum.
+    pub fn spectrum_window(mut self, window: WindowFunction) -> Self {
+        self.config.spectrum_window = window;
+        self
+    }
+
+    /// Sets the color of the held maximum drawn by `plot_spectrum_maxhold`.
+    pub fn max_hold_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.config.max_hold_color = [r, g, b];
+        self
+    }
+
+    /// Fixes the x limits, turning off autoscaling of the x axis. If the x
+    /// axis is linked, the linked figures follow on their next draw.
+    pub fn set_xlim(&mut self, xlim: [f32; 2]) {
+        self.config.xlim = Some(xlim);
+        self.x_dynamic = false;
+        publish(&mut self.x_link, xlim);
+    }
+
+    /// Fixes the y limits, as with `set_xlim`.
+    pub fn set_ylim(&mut self, ylim: [f32; 2]) {
+        self.config.ylim = Some(ylim);
+        self.y_dynamic = false;
+        publish(&mut self.y_link, ylim);
+    }
+
+    /// Fits the limits to the data from the last draw and then holds them
+    /// there, turning off autoscaling. This is the opposite of continuous
+    /// autoscaling, for catching a good view of streaming data and keeping
+    /// it. Does nothing if nothing has been drawn yet.
+    pub fn fit_once(&mut self) {
+        if self.last_points.is_empty() {
+            return;
+        }
+        self.set_xlim(utils::calc_xlims(&self.last_points));
+        self.set_ylim(utils::calc_ylims(&self.last_points));
+    }
+
+    /// Links the x axis to a shared axis, so that whenever any figure linked
+    /// to it changes its x limits, by autoscaling, zooming or `set_xlim`, the
+    /// others take on the same limits. Following another figure's limits
+    /// turns off autoscaling of the axis, leaving one figure to lead.
+    pub fn link_x(&mut self, axis: &SharedAxis) {
+        self.x_link = Some((axis.clone(), 0));
+    }
+
+    /// Links the y axis to a shared axis, as with `link_x`.
+    pub fn link_y(&mut self, axis: &SharedAxis) {
+        self.y_link = Some((axis.clone(), 0));
+    }
+
+    /// Unlinks both axes from any shared axes.
+    pub fn unlink(&mut self) {
+        self.x_link = None;
+        self.y_link = None;
+    }
+
+    /// Turns autoscaling of the x axis on or off. Turning autoscaling on
+    /// clears any x limits. Turning it off freezes the x limits where they
+    /// are. Takes effect on the next draw.
+    pub fn set_autoscale_x(&mut self, autoscale: bool) {
+        self.x_dynamic = autoscale;
+        if autoscale {
+            self.config.xlim = None;
+        }
+    }
+
+    /// Turns autoscaling of the y axis on or off, as with
+    /// `set_autoscale_x`.
+    pub fn set_autoscale_y(&mut self, autoscale: bool) {
+        self.y_dynamic = autoscale;
+        if autoscale {
+            self.config.ylim = None;
+        }
+    }
+
+    /// Changes the x label, such as to show a value that changes over time.
+    /// Takes effect on the next draw.
+    pub fn set_xlabel(&mut self, xlabel: impl Into<Cow<'a, str>>) {
+        self.config.xlabel = Some(xlabel.into());
+    }
+
+    /// Changes the y label. Takes effect on the next draw.
+    pub fn set_ylabel(&mut self, ylabel: impl Into<Cow<'a, str>>) {
+        self.config.ylabel = Some(ylabel.into());
+    }
+
+    /// Draws a function of x as a curve over the data on every frame, such as
+    /// a theoretical model to compare noisy measurements against. The
+    /// function is sampled once per pixel across the current x limits, so
+    /// the curve stays smooth however sparse the data is, and is drawn in
+    /// `reference_color`.
+    pub fn set_reference_fn(&mut self, f: impl Fn(f32) -> f32 + 'a) {
+        self.window.set_reference_fn(Some(Box::new(f)));
+    }
+
+    /// Marks events, such as detected packets, with short ticks along the
+    /// bottom of the plot at the given x positions. The marks stay until
+    /// cleared, so they move along with the x axis as its limits change.
+    /// Each mark carries the label, if any, though labels are skipped where
+    /// they'd crowd the one before.
+    pub fn mark_events(&mut self, times: &[f32], label: Option<&str>) {
+        self.window.add_events(times, label);
+    }
+
+    /// Removes every event marked with `mark_events`.
+    pub fn clear_events(&mut self) {
+        self.window.clear_events();
+    }
+
+    /// Draws a custom path, such as an arrow, a shaded region or any other
+    /// shape the plot types don't cover, on top of everything else on every
+    /// frame until cleared. The path is in data coordinates, so it moves and
+    /// scales with the limits, and isn't drawn until both limits are known.
+    /// Points outside the limits aren't clipped.
+    pub fn add_path(&mut self, path: lyon::path::Path, color: [u8; 3], style: PathStyle) {
+        self.window.add_path(path, color, style);
+    }
+
+    /// Removes every path added with `add_path`.
+    pub fn clear_paths(&mut self) {
+        self.window.clear_paths();
+    }
+
+    /// Stops drawing the curve set by `set_reference_fn`.
+    pub fn clear_reference_fn(&mut self) {
+        self.window.set_reference_fn(None);
+    }
+
+    /// Changes the color of the line to draw, making it opaque. Takes effect
+    /// on the next draw.
+    pub fn set_color(&mut self, r: u8, g: u8, b: u8) {
+        self.config.color = Color::from([r, g, b]);
+    }
+
+    /// Draws the plot in the `below` color where y is under `level` and in
+    /// the `above` color everywhere else, splitting lines where they cross the
+    /// level. Takes effect on the next draw.
+    pub fn color_by_threshold(&mut self, level: f32, below: [u8; 3], above: [u8; 3]) {
+        self.config.color_threshold = Some(ColorThreshold {
+            level,
+            below,
+            above,
+        });
+    }
+
+    /// Sets the type of plot to generate.
+    pub fn plot_type(mut self, plot_type: PlotType) -> Self {
+        self.config.plot_type = plot_type;
+        self
+    }
+
+    /// Sets the y value that Stem, Bar and Area plots are drawn from.
+    pub fn baseline(mut self, baseline: f32) -> Self {
+        self.config.baseline = baseline;
+        self
+    }
+
+    /// Sets whether to overlay the time taken to draw the last frame.
+    pub fn show_fps(mut self, show_fps: bool) -> Self {
+        self.config.show_fps = show_fps;
+        self
+    }
+
+    /// Returns how many points fell outside the limits in the last draw.
+    /// Depending on `clip_mode`, these were dropped, moved onto the edges of
+    /// the plot or drawn past them. A nonzero count with fixed limits
+    /// usually means the limits don't cover the data.
+    pub fn clipped_count(&self) -> usize {
+        self.clipped
+    }
+
+    /// Returns how many points were left to draw in the last draw, after any
+    /// outside the limits were dropped. Zero with a nonzero `clipped_count`
+    /// means every point was dropped, rather than there being no data.
+    pub fn last_vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Returns whether the last draw had more points than `max_points`
+    /// allows and was decimated to fit.
+    pub fn was_decimated(&self) -> bool {
+        self.decimated
+    }
+
+    /// Returns the x and y limits of the data actually on screen, as of the
+    /// last draw, after any autoscaling, zooming or panning. Unlike the
+    /// limits in the configuration, these are always the ones drawn with,
+    /// including for draws that fit their own limits such as
+    /// `plot_digital_channels`. This makes them suitable for keeping linked
+    /// views in sync. Returns None if nothing has been drawn yet.
+    pub fn visible_range(&self) -> Option<([f32; 2], [f32; 2])> {
+        self.visible
+    }
+
+    /// Returns the current size of the window's framebuffer in pixels. This
+    /// follows the window as it's resized.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        self.window.framebuffer_size()
+    }
+
+    /// Returns the current aspect ratio of the window, its width over its
+    /// height.
+    pub fn aspect(&self) -> f32 {
+        let (w, h) = self.window.framebuffer_size();
+        w as f32 / h as f32
+    }
+
+    /// Maps a point in data coordinates to normalized device coordinates in
+    /// the window, using the limits from the last draw. Returns None if
+    /// nothing has been drawn yet.
+    pub fn data_to_screen(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let (xlim, ylim) = self.visible?;
+        let extent = self.config.layout.plot_extent;
+        let [half_w, half_h] = self.window.half_extents(&self.config);
+        let (x, y) = utils::normalize_point((x, y), xlim, ylim, extent);
+        Some((x / half_w, y / half_h))
+    }
+
+    /// Maps a point in normalized device coordinates in the window to data
+    /// coordinates, using the limits from the last draw. Returns None if
+    /// nothing has been drawn yet.
+    pub fn screen_to_data(&self, x_ndc: f32, y_ndc: f32) -> Option<(f32, f32)> {
+        let (xlim, ylim) = self.visible?;
+        let extent = self.config.layout.plot_extent;
+        let [half_w, half_h] = self.window.half_extents(&self.config);
+        Some((
+            utils::denormalize_value(x_ndc * half_w, xlim, extent),
+            utils::denormalize_value(y_ndc * half_h, ylim, extent),
+        ))
+    }
+
+    /// Returns the point from the last draw closest on screen to a position
+    /// in normalized device coordinates, in data coordinates. This is the
+    /// building block for tooltips and click-to-select. Returns None if the
+    /// last draw had no points.
+    pub fn nearest_point(&self, x_ndc: f32, y_ndc: f32) -> Option<(f32, f32)> {
+        let distance = |point: &Point2<f32>| {
+            self.data_to_screen(point.x, point.y)
+                .map(|(x, y)| (x - x_ndc).powi(2) + (y - y_ndc).powi(2))
+                .unwrap_or(f32::INFINITY)
+        };
+        self.last_points
+            .iter()
+            .filter(|point| point.x.is_finite() && point.y.is_finite())
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .map(|point| (point.x, point.y))
+    }
+
+    /// Sets a callback to run after every frame is presented, with details
+    /// such as the frame's index, timestamp, frame rate and the number of
+    /// points drawn. This is handy for logging or recording frame times
+    /// without restructuring the plotting loop. Frames rendered offscreen
+    /// with `render_to_rgba` don't count.
+    pub fn on_frame(&mut self, callback: impl FnMut(&FrameInfo) + 'a) {
+        self.window.set_on_frame(Some(Box::new(callback)));
+    }
+
+    /// Returns how long the most recent frame took to tessellate and draw.
+    pub fn last_frame_time(&self) -> Duration {
+        self.window.last_frame_time
+    }
+
+    /// Checks events to see if the figure should close or not. Returns
+    /// true if the window received a close event, false otherwise. In
+    /// most cases, you don't need to handle events yourself; use
+    /// Figure::display() instead.
+    pub fn should_close_window(&mut self) -> bool {
+        let mut should_close_window = false;
+        let mut inputs = vec![];
+
+        let events_loop = match &mut self.window.events_loop {
+            Some(events_loop) => events_loop,
+            None => return false,
+        };
+
+        events_loop.run_return(|event, _, control_flow| {
+            use glium::glutin::event::{
+                ElementState, Event, KeyboardInput, MouseButton, WindowEvent,
+            };
+            use glium::glutin::event_loop::ControlFlow;
+            match event {
+                Event::WindowEvent {
+                    event: WindowEvent::Destroyed | WindowEvent::CloseRequested,
+                    ..
+                } => should_close_window = true,
+                Event::WindowEvent {
+                    event:
+                        WindowEvent::KeyboardInput {
+                            input:
+                                KeyboardInput {
+                                    state: ElementState::Pressed,
+                                    virtual_keycode: Some(key),
+                                    ..
+                                },
+                            ..
+                        },
+                    ..
+                } => inputs.push(Input::Key(key)),
+                Event::WindowEvent {
+                    event: WindowEvent::CursorMoved { position, .. },
+                    ..
+                } => inputs.push(Input::CursorMoved(position.x, position.y)),
+                Event::WindowEvent {
+                    event:
+                        WindowEvent::MouseInput {
+                            state,
+                            button: MouseButton::Left,
+                            ..
+                        },
+                    ..
+                } => inputs.push(match state {
+                    ElementState::Pressed => Input::MousePressed,
+                    ElementState::Released => Input::MouseReleased,
+                }),
+                Event::WindowEvent {
+                    event: WindowEvent::Focused(true) | WindowEvent::Resized(_),
+                    ..
+                }
+                | Event::RedrawRequested(_) => inputs.push(Input::Redraw),
+                _ => (),
+            }
+            *control_flow = ControlFlow::Exit;
+        });
+        let mut redraw = false;
+        for input in inputs {
+            match input {
+                Input::Key(key) => self.handle_key(key),
+                Input::CursorMoved(x, y) => {
+                    let (w, h) = self.window.framebuffer_size();
+                    self.cursor = Some([
+                        2.0 * x as f32 / w as f32 - 1.0,
+                        1.0 - 2.0 * y as f32 / h as f32,
+                    ]);
+                }
+                Input::MousePressed => self.drag_start = self.cursor,
+                Input::MouseReleased => {
+                    if let (Some(start), Some(end)) = (self.drag_start.take(), self.cursor) {
+                        self.zoom_to_selection(start, end);
+                    }
+                }
+                Input::Redraw => redraw = true,
+            }
+        }
+        if redraw && self.config.keep_last_draw {
+            let config = self.drawn_config();
+            self.window.redraw(&config);
+        }
+        let [half_w, half_h] = self.window.half_extents(&self.config);
+        let selection = match (self.drag_start, self.cursor) {
+            (Some([x0, y0]), Some([x1, y1])) => {
+                Some([[x0 * half_w, y0 * half_h], [x1 * half_w, y1 * half_h]])
+            }
+            _ => None,
+        };
+        self.window.set_selection(selection);
+        should_close_window
+    }
+
+    /// Fixes the limits to the region of data between two corners of a
+    /// selection, in normalized device coordinates, turning off autoscaling.
+    /// A selection with no width or height is ignored.
+    fn zoom_to_selection(&mut self, start: [f32; 2], end: [f32; 2]) {
+        let (xlim, ylim) = match self.visible {
+            Some(visible) => visible,
+            None => return,
+        };
+        let [half_w, half_h] = self.window.half_extents(&self.config);
+        let start = [start[0] * half_w, start[1] * half_h];
+        let end = [end[0] * half_w, end[1] * half_h];
+        let extent = self.config.layout.plot_extent;
+        if let Some((xlim, ylim)) = selection_limits(start, end, xlim, ylim, extent) {
+            self.set_xlim(xlim);
+            self.set_ylim(ylim);
+        }
+    }
+
+    /// Responds to a key press. Space pauses and resumes streaming plots.
+    /// While paused, the left and right arrows step the view through the
+    /// queue one sample at a time, and the up and down arrows zoom the view
+    /// in and out. Escape cancels selecting a region to zoom to, and F fits
+    /// the limits to the data on screen once and then holds them.
+    fn handle_key(&mut self, key: VirtualKeyCode) {
+        let len = self.samples.len().max(self.complex_samples.len());
+        match key {
+            VirtualKeyCode::F => self.fit_once(),
+            VirtualKeyCode::R => self.rearm(),
+            VirtualKeyCode::Escape => self.drag_start = None,
+            VirtualKeyCode::Space => self.set_paused(!self.paused),
+            VirtualKeyCode::Left if self.paused => {
+                self.view_offset = self.view_offset.saturating_sub(1);
+            }
+            VirtualKeyCode::Right if self.paused => {
+                let view_len = self.view_len.unwrap_or(len);
+                self.view_offset = (self.view_offset + 1).min(len.saturating_sub(view_len));
+            }
+            VirtualKeyCode::Up if self.paused => {
+                let view_len = self.view_len.unwrap_or(len);
+                self.view_len = Some((view_len / 2).max(2));
+            }
+            VirtualKeyCode::Down if self.paused => {
+                let view_len = self.view_len.unwrap_or(len).saturating_mul(2);
+                self.view_len = if view_len >= len {
+                    None
+                } else {
+                    Some(view_len)
+                };
+                self.view_offset = self.view_offset.min(len.saturating_sub(view_len));
+            }
+            _ => (),
+        }
+    }
+
+    /// Pauses or resumes streaming plots. While paused, new samples passed to
+    /// `plot_stream` and `plot_complex_stream` are discarded and the queue is
+    /// shown as it was when paused. Resuming shows the whole queue again.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.view_offset = 0;
+        self.view_len = None;
+        if !paused {
+            self.window.set_status(None);
+        }
+    }
+
+    /// Empties the queues of real and complex samples, making room for more
+    /// under `Eviction::Block`. Takes effect on the next draw.
+    pub fn clear_samples(&mut self) {
+        self.samples.clear();
+        self.complex_samples.clear();
+    }
+
+    /// Returns whether streaming plots are paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns the range of samples to show from a queue of the given length,
+    /// which is the whole queue unless paused.
+    fn view(&mut self, len: usize) -> Range<usize> {
+        if !self.paused {
+            return 0..len;
+        }
+        let view_len = self.view_len.unwrap_or(len).min(len);
+        let start = self.view_offset.min(len - view_len);
+        let status = format!(
+            "Paused: samples {}-{} of {}",
+            start,
+            (start + view_len).saturating_sub(1),
+            len
+        );
+        self.window.set_status(Some(status));
+        start..start + view_len
+    }
+
+    /// Normalizes the received points to [-0.5, 0.5] for drawing in OpenGL.
+    /// If `equal_axes` is set and both axes are autoscaled, the axes share the
+    /// same limits so that a unit on one is the same size as on the other.
+    fn normalize(&mut self, points: &[Point2<f32>], equal_axes: bool) -> Vec<Vertex> {
+        let (xlim, ylim) = self.update_limits(points, equal_axes);
+        self.map_to_plot(points, xlim, ylim)
+    }
+
+    /// Autoscales any dynamic axes to fit the points, returning the limits to
+    /// plot against.
+    fn update_limits(&mut self, points: &[Point2<f32>], equal_axes: bool) -> ([f32; 2], [f32; 2]) {
+        self.follow_links();
+        if self.seeded {
+            if points.len() < self.queue_size.max(1) {
+                if let (Some(xlim), Some(ylim)) = (self.config.xlim, self.config.ylim) {
+                    return (xlim, ylim);
+                }
+            }
+            self.seeded = false;
+        }
+        let smoothing = self.config.limit_smoothing;
+        // An axis with autoscaling turned off but no limits yet is fit to the
+        // first data drawn and then stays put.
+        let x_fit = self.x_dynamic || self.config.xlim.is_none();
+        let y_fit = self.y_dynamic || self.config.ylim.is_none();
+        let [min_x, max_x] = match self.config.xlim {
+            Some(xlim) if !x_fit => xlim,
+            _ => utils::calc_xlims(points),
+        };
+        let [min_y, max_y] = match self.config.ylim {
+            Some(ylim) if !y_fit => ylim,
+            _ => utils::calc_ylims(points),
+        };
+        let (xlim, ylim) = if equal_axes && x_fit && y_fit {
+            let lims = [min_x.min(min_y), max_x.max(max_y)];
+            (lims, lims)
+        } else {
+            ([min_x, max_x], [min_y, max_y])
+        };
+        if x_fit {
+            let xlim = smooth_limits(self.config.xlim, xlim, smoothing);
+            self.config.xlim = Some(xlim);
+            publish(&mut self.x_link, xlim);
+        }
+        if y_fit {
+            let ylim = smooth_limits(self.config.ylim, ylim, smoothing);
+            self.config.ylim = Some(ylim);
+            publish(&mut self.y_link, ylim);
+        }
+        (self.config.xlim.unwrap(), self.config.ylim.unwrap())
+    }
+
+    /// Fixes any linked axes to limits another figure has set on them since
+    /// this figure last looked.
+    fn follow_links(&mut self) {
+        if let Some((axis, seen)) = &mut self.x_link {
+            if axis.generation() != *seen {
+                *seen = axis.generation();
+                if let Some(xlim) = axis.get() {
+                    self.config.xlim = Some(xlim);
+                    self.x_dynamic = false;
+                }
+            }
+        }
+        if let Some((axis, seen)) = &mut self.y_link {
+            if axis.generation() != *seen {
+                *seen = axis.generation();
+                if let Some(ylim) = axis.get() {
+                    self.config.ylim = Some(ylim);
+                    self.y_dynamic = false;
+                }
+            }
+        }
+    }
+
+    /// Maps the points onto the plot area using the given limits, counting
+    /// how many fell outside them and how many are left to draw. Decimates
+    /// the result if it's over the point budget.
+    fn map_to_plot(
+        &mut self,
+        points: &[Point2<f32>],
+        xlim: [f32; 2],
+        ylim: [f32; 2],
+    ) -> Vec<Vertex> {
+        let mut vertices = std::mem::take(&mut self.scratch_vertices);
+        vertices.clear();
+        let clipped = map_points_into(points, xlim, ylim, &self.config, &mut vertices);
+        self.last_points.clear();
+        self.last_points.extend_from_slice(points);
+        self.visible = Some((xlim, ylim));
+        self.decimated = false;
+        if let Some(max_points) = self.config.max_points {
+            if vertices.len() > max_points {
+                vertices = decimate(&vertices, max_points);
+                self.decimated = true;
+            }
+        }
+        self.clipped = clipped;
+        self.vertex_count = vertices.len();
+        vertices
+    }
+
+    /// Updates the statistics shown by `show_stats` from the y values of the
+    /// points about to be drawn.
+    fn update_stats(&mut self, points: &[Point2<f32>]) {
+        // Reuse the lines from the last draw rather than allocating new ones.
+        let mut lines = self.window.take_stats().unwrap_or_default();
+        let stats = if self.config.show_stats {
+            utils::calc_stats(points.iter().map(|pt| pt.y)).map(|stats| {
+                let format = self.config.number_format;
+                let values = [
+                    ("min", stats.min),
+                    ("max", stats.max),
+                    ("mean", stats.mean),
+                    ("rms", stats.rms),
+                ];
+                lines.resize(values.len(), String::new());
+                for (line, (name, value)) in lines.iter_mut().zip(values.iter()) {
+                    line.clear();
+                    line.push_str(name);
+                    line.push(' ');
+                    line.push_str(&format.format(*value, 3));
+                }
+                lines
+            })
+        } else {
+            None
+        };
+        self.window.set_stats(stats);
+    }
+
+    /// A helper function for normalizing and drawing points to the window.
+    fn plot(&mut self, points: &[Point2<f32>]) {
+        self.update_stats(points);
+        let vertices = self.normalize(points, false);
+        self.window.draw(&vertices, &[], &self.config);
+        self.scratch_vertices = vertices;
+    }
+
+    /// Take an array of 2D points and draw them to the plot. This overrides
+    /// any samples in the queue.
+    pub fn plot_xy<T>(&mut self, points: &[(T, T)])
+    where
+        T: Into<f32> + Copy,
+    {
+        let mut scratch = std::mem::take(&mut self.scratch_points);
+        scratch.clear();
+        scratch.extend(
+            points
+                .iter()
+                .map(|pt| Point2::new(pt.0.into(), pt.1.into())),
+        );
+        self.plot_accumulating(&scratch, Self::plot);
+        self.scratch_points = scratch;
+    }
+
+    /// Adds the points to the accumulated points and draws them all with
+    /// `draw` if `accumulate` is set. Otherwise, draws the points as they
+    /// are. The accumulated points are drawn in place rather than copied.
+    fn plot_accumulating<F>(&mut self, points: &[Point2<f32>], draw: F)
+    where
+        F: FnOnce(&mut Self, &[Point2<f32>]),
+    {
+        let accumulating = self.config.accumulate
+            && accumulate(
+                &mut self.accumulated,
+                points,
+                self.queue_size,
+                self.config.eviction,
+            );
+        if accumulating {
+            let accumulated = std::mem::take(&mut self.accumulated);
+            draw(self, &accumulated);
+            self.accumulated = accumulated;
+        } else {
+            draw(self, points);
+        }
+    }
+
+    /// Discards the points kept by `accumulate`.
+    pub fn clear_accumulated(&mut self) {
+        self.accumulated.clear();
+    }
+
+    /// Draws the most recently plotted data again without recomputing it, so
+    /// changes that only affect how the plot looks, such as its color or
+    /// plot type, show up without waiting for new data. The data stays where
+    /// it was mapped onto the plot, so changes to the limits don't apply to
+    /// it. The figure's own configuration is used, so settings a plot method
+    /// applied for a single draw, like the bars of `plot_bar_categorical`,
+    /// aren't kept. A frame composited with `begin_frame` and `end_frame` is
+    /// presented again from its mesh. Nothing is kept to draw again unless
+    /// `keep_last_draw` is set, so this does nothing otherwise.
+    pub fn redraw(&mut self) {
+        self.window.redraw_with(&self.config);
+    }
+
+    /// Starts compositing a frame. Until `end_frame` is called, every plot
+    /// call adds its data to the frame instead of drawing a frame of its own,
+    /// so several calls can be shown together, such as data alongside a
+    /// reference line drawn with `draw_raw`. Each call still autoscales
+    /// against its own data, so set limits if the layers need to line up.
+    /// Data in the frame is always tessellated, even with `fast_render` set.
+    pub fn begin_frame(&mut self) {
+        self.window.begin_frame();
+    }
+
+    /// Presents everything plotted since `begin_frame` in a single frame,
+    /// with the grid and labels drawn against the current limits.
+    pub fn end_frame(&mut self) {
+        self.window.present(&self.config);
+    }
+
+    /// Composites every plot call made in the closure into a single frame, as
+    /// with `begin_frame` and `end_frame`.
+    pub fn frame<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.begin_frame();
+        draw(self);
+        self.end_frame();
+    }
+
+    /// Draws points in data coordinates against the current limits in the
+    /// given color, without autoscaling and without touching the samples in
+    /// the queue. If the limits haven't been set or fit to any data yet, they
+    /// are fit to these points. This is an escape hatch for custom geometry
+    /// the other plot methods don't cover.
+    pub fn draw_raw(&mut self, points: &[(f32, f32)], color: impl Into<Color>) {
+        let points: Vec<Point2<f32>> = points.iter().map(|pt| Point2::new(pt.0, pt.1)).collect();
+        let (xlim, ylim) = match (self.config.xlim, self.config.ylim) {
+            (Some(xlim), Some(ylim)) => (xlim, ylim),
+            _ => self.update_limits(&points, false),
+        };
+        let vertices = self.map_to_plot(&points, xlim, ylim);
+        let mut config = self.config.clone();
+        config.color = color.into();
+        self.window.draw(&vertices, &[], &config);
     }
 
     /// Takes a series of real samples and draws them onto the plot. This
-    /// overrides any samples in the queue. The x-axis will be interpolated.
+    /// overrides any samples in the queue. Each sample is plotted against its
+    /// index, so autoscaled x ticks show sample indices.
     pub fn plot_y<T>(&mut self, y_coords: &[T])
     where
         T: Into<f32> + Copy,
     {
-        let x_coords = linspace(-0.5f32, 0.5f32, y_coords.len());
+        // When accumulating, carry on counting from the last sample kept.
+        let start = match self.accumulated.back() {
+            Some(last) if self.config.accumulate => last.x + 1.0,
+            _ => 0.0,
+        };
+        let mut points = std::mem::take(&mut self.scratch_points);
+        points.clear();
+        points.extend(
+            y_coords
+                .iter()
+                .enumerate()
+                .map(|(i, y)| Point2::new(start + i as f32, (*y).into())),
+        );
+        self.plot_accumulating(&points, Self::plot);
+        self.scratch_points = points;
+    }
+
+    /// Takes samples along with the time each was taken and draws them
+    /// against those times, for irregularly sampled data such as sensor logs
+    /// with jittery timestamps. The x axis spans the actual range of times and
+    /// its ticks are in the same units. If the slices differ in length, the
+    /// extra values in the longer one are ignored. This overrides any samples
+    /// in the queue.
+    pub fn plot_tx_y(&mut self, t: &[f32], y: &[f32]) {
+        let mut points = std::mem::take(&mut self.scratch_points);
+        points.clear();
+        points.extend(t.iter().zip(y.iter()).map(|(t, y)| Point2::new(*t, *y)));
+        self.plot_accumulating(&points, Self::plot);
+        self.scratch_points = points;
+    }
+
+    /// Draws the y values as with `plot_y`, colored along the colormap from
+    /// the first value to the last so the progression of the data is
+    /// visible. The colormap only applies to this draw.
+    pub fn plot_y_gradient<T>(&mut self, y_coords: &[T], cmap: Colormap)
+    where
+        T: Into<f32> + Copy,
+    {
+        let points: Vec<Point2<f32>> = y_coords
+            .iter()
+            .enumerate()
+            .map(|(i, y)| Point2::new(i as f32, (*y).into()))
+            .collect();
+        let vertices = self.normalize(&points, false);
+        let mut config = self.config.clone();
+        config.colormap = Some(cmap);
+        self.window.draw(&vertices, &[], &config);
+    }
+
+    /// Takes a series of real samples and draws them onto the plot. Samples
+    /// received from the stream are appended to the queue and any samples
+    /// exceeding the queue size are evicted according to `eviction`. The
+    /// x-axis will be interpolated.
+    pub fn plot_stream<T>(&mut self, y_coords: &[T])
+    where
+        T: Into<f32> + Copy,
+    {
+        if self.queue_size == 0 {
+            return;
+        }
+        self.enqueue_samples(y_coords.iter().map(|y| (*y).into()));
+        self.draw_stream();
+    }
+
+    /// Adds several chunks of real samples to the queue and draws them once,
+    /// for sources that deliver samples in bursts. The queue ends up the same
+    /// as calling `plot_stream` for each chunk in turn, but eviction runs once
+    /// over all the new samples and the plot is only redrawn once.
+    pub fn plot_stream_batch(&mut self, chunks: &[&[f32]]) {
+        if self.queue_size == 0 {
+            return;
+        }
+        self.enqueue_samples(chunks.iter().flat_map(|chunk| chunk.iter().copied()));
+        self.draw_stream();
+    }
+
+    /// Draws the queue of real samples.
+    fn draw_stream(&mut self) {
+        if let (Some(samples_per_pixel), false) = (self.config.samples_per_pixel, self.paused) {
+            self.plot_stream_scrolling(samples_per_pixel);
+            return;
+        }
+        let view = self.view(self.samples.len());
+        let x_coords = linspace(-0.5f32, 0.5f32, self.queue_size);
         let points: Vec<Point2<f32>> = x_coords
-            .zip(y_coords.iter())
-            .map(|(x, y)| Point2::new(x, (*y).into()))
+            .zip(self.samples.iter())
+            .map(|(x, y)| Point2::new(x, *y))
+            .skip(view.start)
+            .take(view.len())
             .collect();
-        self.plot(&points);
+        self.update_stats(&points);
+        let vertices = self.normalize(&points, false);
+        self.window.draw(&vertices, &[], &self.config);
     }
 
-    /// Takes a series of real samples and draws them onto the plot. Samples
-    /// received from the stream are appended to the queue and any samples
-    /// exceeding the queue size are removed. The x-axis will be interpolated.
-    pub fn plot_stream<T>(&mut self, y_coords: &[T])
+    /// Adds the live samples to the queue as with `plot_stream` and draws them
+    /// over a fixed reference curve, such as an expected template, in
+    /// `overlay_color`. The reference isn't queued; it's drawn as given each
+    /// frame, stretched across the same x span as the queue. Both share the
+    /// same limits.
+    pub fn plot_stream_with_overlay<T>(&mut self, live: &[T], overlay: &[f32])
     where
         T: Into<f32> + Copy,
     {
-        if self.samples.len() >= self.queue_size + y_coords.len() {
-            for _ in 0..self.samples.len() - self.queue_size + y_coords.len() {
-                self.samples.pop_front();
-            }
+        if self.queue_size == 0 {
+            return;
         }
-        let y: Vec<f32> = y_coords.iter().map(|y| (*y).into()).collect();
-        for point in &y {
-            self.samples.push_back(*point);
-        }
-        let x_coords = linspace(-0.5f32, 0.5f32, self.queue_size);
-        let points: Vec<Point2<f32>> = x_coords
+        self.enqueue_samples(live.iter().map(|y| (*y).into()));
+        let view = self.view(self.samples.len());
+        let points: Vec<Point2<f32>> = linspace(-0.5f32, 0.5f32, self.queue_size)
             .zip(self.samples.iter())
             .map(|(x, y)| Point2::new(x, *y))
+            .skip(view.start)
+            .take(view.len())
+            .collect();
+        // While paused, only show the part of the reference alongside the
+        // samples in view.
+        let span = match (self.paused, points.first(), points.last()) {
+            (true, Some(first), Some(last)) => [first.x, last.x],
+            _ => [-0.5, 0.5],
+        };
+        let reference: Vec<Point2<f32>> = linspace(-0.5f32, 0.5f32, overlay.len())
+            .zip(overlay.iter())
+            .map(|(x, y)| Point2::new(x, *y))
+            .filter(|pt| pt.x >= span[0] && pt.x <= span[1])
             .collect();
-        let vertices = self.normalize(&points);
-        self.window.draw(&vertices, &self.config);
+
+        let all_points: Vec<Point2<f32>> = points.iter().chain(reference.iter()).cloned().collect();
+        let (xlim, ylim) = self.update_limits(&all_points, false);
+        self.update_stats(&points);
+        let vertices = self.map_to_plot(&points, xlim, ylim);
+        let overlay = Overlay {
+            vertices: map_points(&reference, xlim, ylim, &self.config).0,
+            color: self.config.overlay_color.into(),
+        };
+        self.window.draw(&vertices, &[overlay], &self.config);
     }
 
-    /// Takes a slice of complex samples and draws them onto the plot. Samples
-    /// received from the stream are appended to the queue and any samples
-    /// exceeding the queue size are removed.
-    pub fn plot_complex_stream<T>(&mut self, points: &[Complex<T>])
+    /// Adds real samples to the queue unless paused, first filling an empty
+    /// queue according to `prefill`.
+    fn enqueue_samples(&mut self, samples: impl Iterator<Item = f32>) {
+        if self.paused {
+            return;
+        }
+        let condition = self.config.capture.filter(|_| self.armed);
+        let mut prev = self.samples.back().copied();
+        let mut triggered = false;
+        let samples = samples.inspect(|&value| {
+            if let Some(condition) = condition {
+                triggered |= condition.met(prev, value);
+            }
+            prev = Some(value);
+        });
+        if self.samples.is_empty() {
+            if let (Some(fill), Eviction::DropOldest) =
+                (self.config.prefill.value(), self.config.eviction)
+            {
+                self.samples
+                    .extend(std::iter::repeat_n(fill, self.queue_size));
+            }
+        }
+        enqueue(
+            &mut self.samples,
+            samples,
+            self.queue_size,
+            self.config.eviction,
+        );
+        if triggered {
+            self.armed = false;
+            self.set_paused(true);
+        }
+    }
+
+    /// Resumes the plot after a capture and waits for the capture condition
+    /// to be met again. Does nothing if no capture condition is set.
+    pub fn rearm(&mut self) {
+        if self.config.capture.is_some() {
+            self.armed = true;
+            self.set_paused(false);
+        }
+    }
+
+    /// Draws the newest samples in the queue at a fixed number of samples per
+    /// pixel, so the trace scrolls across the plot as samples arrive.
+    fn plot_stream_scrolling(&mut self, samples_per_pixel: f32) {
+        let width = self.window.plot_width(&self.config);
+        let visible = ((width * samples_per_pixel).round() as usize).max(2);
+        let start = self.samples.len().saturating_sub(visible);
+        let points: Vec<Point2<f32>> = self.samples[start..]
+            .iter()
+            .enumerate()
+            .map(|(i, y)| Point2::new(i as f32, *y))
+            .collect();
+
+        // Fix the x axis to exactly the visible span for this draw, leaving
+        // the figure's own x limits untouched afterwards.
+        let (xlim, x_dynamic) = (self.config.xlim, self.x_dynamic);
+        self.config.xlim = Some([0.0, (visible - 1) as f32]);
+        self.x_dynamic = false;
+        self.update_stats(&points);
+        let vertices = self.normalize(&points, false);
+        let config = self.config.clone();
+        self.config.xlim = xlim;
+        self.x_dynamic = x_dynamic;
+        self.window.draw(&vertices, &[], &config);
+    }
+
+    /// Bins each frame of samples, plotted against their index, into a grid
+    /// that persists between calls and draws it as a heatmap, building up a
+    /// picture of where the signal spends its time, such as the spread of
+    /// noise. Cells are colored along `colormap`, or viridis if none is set,
+    /// on a log scale of how many samples landed in them so rare excursions
+    /// stay visible next to the dense core. Counts are never negative, so they
+    /// run along the whole colormap even if it's diverging, from the first
+    /// color for the emptiest cells to the last for the fullest. The value
+    /// range of the grid is
+    /// fixed by the first frame: the y limits if set, or else the range of
+    /// that frame's values. Values outside the range aren't counted. A frame
+    /// of a different length starts a new grid. This overrides any samples in
+    /// the queue.
+    pub fn plot_density_stream<T>(&mut self, values: &[T])
     where
         T: Into<f32> + Copy,
     {
-        if self.complex_samples.len() >= self.queue_size + points.len() {
-            for _ in 0..self.complex_samples.len() - self.queue_size + points.len() {
-                self.complex_samples.pop_front();
+        let values: Vec<f32> = values.iter().map(|v| (*v).into()).collect();
+        if values.is_empty() {
+            return;
+        }
+        let rows = self.config.density_rows.unwrap_or(64).max(1);
+        let stale = match &self.density {
+            Some(grid) => grid.columns != values.len() || grid.rows != rows,
+            None => true,
+        };
+        if stale {
+            let range = match self.config.ylim {
+                Some([min, max]) => [min.min(max), min.max(max)],
+                None => match utils::calc_stats(values.iter().copied()) {
+                    Some(stats) if stats.max > stats.min => [stats.min, stats.max],
+                    Some(stats) => [stats.min - 0.5, stats.max + 0.5],
+                    None => return,
+                },
+            };
+            self.density = Some(DensityGrid {
+                columns: values.len(),
+                rows,
+                range,
+                counts: vec![0; values.len() * rows],
+            });
+        }
+        let grid = match self.density.as_mut() {
+            Some(grid) => grid,
+            None => return,
+        };
+        let [min, max] = grid.range;
+        for (column, value) in values.iter().enumerate() {
+            if !(min..=max).contains(value) {
+                continue;
             }
+            let row = (((value - min) / (max - min) * rows as f32) as usize).min(rows - 1);
+            grid.counts[row * grid.columns + column] += 1;
         }
 
-        let points: Vec<Complex<f32>> = points
+        let (columns, range) = (grid.columns, grid.range);
+        let peak = grid.counts.iter().copied().max().unwrap_or(0);
+        let corners = [
+            Point2::new(0.0, range[0]),
+            Point2::new(columns as f32, range[1]),
+        ];
+        let (xlim, ylim) = self.update_limits(&corners, false);
+        self.visible = Some((xlim, ylim));
+        let extent = self.config.layout.plot_extent;
+        let cmap = self.config.colormap.unwrap_or_default();
+        let row_height = (range[1] - range[0]) / rows as f32;
+        let cells: Vec<([f32; 4], [u8; 3])> = self
+            .density
             .iter()
-            .map(|x| Complex::new(x.re.into(), x.im.into()))
+            .flat_map(|grid| grid.counts.iter().enumerate())
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| {
+                let (row, column) = (i / columns, i % columns);
+                let low = range[0] + row as f32 * row_height;
+                let (x0, y0) = utils::normalize_point((column as f32, low), xlim, ylim, extent);
+                let (x1, y1) = utils::normalize_point(
+                    ((column + 1) as f32, low + row_height),
+                    xlim,
+                    ylim,
+                    extent,
+                );
+                let level = (*count as f32).ln_1p() / (peak as f32).ln_1p();
+                ([x0, y0, x1, y1], cmap.sample(level))
+            })
+            .collect();
+        self.window.draw_cells(&cells, &self.config);
+    }
+
+    /// Throws away the density built up by `plot_density_stream`, so the
+    /// next frame starts a new grid.
+    pub fn clear_density(&mut self) {
+        self.density = None;
+    }
+
+    /// Draws a digital trace as a square wave, high where the bit is set and
+    /// low elsewhere, with a vertical edge at each transition. This overrides
+    /// any samples in the queue.
+    pub fn plot_digital(&mut self, bits: &[bool]) {
+        self.plot_digital_channels(&[bits]);
+    }
+
+    /// Draws several digital traces as square waves stacked one above the
+    /// other, with the first channel at the top. Each bit spans one unit
+    /// along the x axis. The limits are fit to the channels for this draw
+    /// only, leaving the figure's own limits untouched.
+    pub fn plot_digital_channels(&mut self, channels: &[&[bool]]) {
+        let len = channels.iter().map(|bits| bits.len()).max().unwrap_or(0);
+        let count = channels.len().max(1) as f32;
+        let xlim = [0.0, len.max(1) as f32];
+        let ylim = [-0.25, 1.5 * (count - 1.0) + 1.25];
+
+        let overlays: Vec<Overlay> = channels
+            .iter()
+            .enumerate()
+            .map(|(channel, bits)| {
+                let low = 1.5 * (count - 1.0 - channel as f32);
+                let points: Vec<Point2<f32>> = bits
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, bit)| {
+                        let y = if *bit { low + 1.0 } else { low };
+                        vec![Point2::new(i as f32, y), Point2::new(i as f32 + 1.0, y)]
+                    })
+                    .collect();
+                Overlay {
+                    vertices: map_points(&points, xlim, ylim, &self.config).0,
+                    color: self.config.color,
+                }
+            })
+            .collect();
+
+        self.visible = Some((xlim, ylim));
+        let mut config = self.config.clone();
+        config.xlim = Some(xlim);
+        config.ylim = Some(ylim);
+        self.window.draw(&[], &overlays, &config);
+    }
+
+    /// Draws several series as lines on the same axes, each plotted against
+    /// its index and autoscaled together. A series given a color is drawn in
+    /// it, while the others take the next color from `color_cycle` in turn,
+    /// starting from the first for each call. This overrides any samples in
+    /// the queue.
+    pub fn plot_many(&mut self, series: &[(&[f32], Option<[u8; 3]>)]) {
+        let points: Vec<Vec<Point2<f32>>> = series
+            .iter()
+            .map(|(ys, _)| {
+                ys.iter()
+                    .enumerate()
+                    .map(|(i, y)| Point2::new(i as f32, *y))
+                    .collect()
+            })
+            .collect();
+        let all: Vec<Point2<f32>> = points.iter().flatten().cloned().collect();
+        let (xlim, ylim) = self.update_limits(&all, false);
+        let mut cycle = self.config.color_cycle.clone();
+        cycle.reset();
+        let overlays: Vec<Overlay> = points
+            .iter()
+            .zip(series.iter())
+            .map(|(points, (_, color))| Overlay {
+                vertices: map_points(points, xlim, ylim, &self.config).0,
+                color: color.unwrap_or_else(|| cycle.next_color()).into(),
+            })
+            .collect();
+
+        self.visible = Some((xlim, ylim));
+        self.window.draw(&[], &overlays, &self.config);
+    }
+
+    /// Draws several series as lines stacked one above the other, with each
+    /// series shifted up by `offset` more than the one before, so many
+    /// similar traces can be compared without overlapping. Samples are
+    /// plotted against their index. The y axis ticks mark each series's
+    /// baseline, at `index * offset`, for this draw only. This overrides any
+    /// samples in the queue.
+    pub fn plot_many_offset(&mut self, series: &[&[f32]], offset: f32) {
+        let stacked: Vec<Vec<Point2<f32>>> = series
+            .iter()
+            .enumerate()
+            .map(|(index, ys)| {
+                let base = index as f32 * offset;
+                ys.iter()
+                    .enumerate()
+                    .map(|(i, y)| Point2::new(i as f32, y + base))
+                    .collect()
+            })
+            .collect();
+        let all: Vec<Point2<f32>> = stacked.iter().flatten().cloned().collect();
+        let (xlim, ylim) = self.update_limits(&all, false);
+        let overlays: Vec<Overlay> = stacked
+            .iter()
+            .map(|points| Overlay {
+                vertices: map_points(points, xlim, ylim, &self.config).0,
+                color: self.config.color,
+            })
+            .collect();
+
+        self.visible = Some((xlim, ylim));
+        let mut config = self.config.clone();
+        config.y_ticks = Some((0..series.len()).map(|i| i as f32 * offset).collect());
+        self.window.draw(&[], &overlays, &config);
+    }
+
+    /// Draws a bar chart with one bar per value, each labelled with the
+    /// matching category. The bars evenly divide the x axis and the y axis is
+    /// autoscaled over the values unless limits have been set. This overrides
+    /// any samples in the queue.
+    pub fn plot_bar_categorical(&mut self, labels: &[&str], values: &[f32]) {
+        let points: Vec<Point2<f32>> = values
+            .iter()
+            .enumerate()
+            .map(|(i, y)| Point2::new(i as f32 + 0.5, *y))
             .collect();
-        for point in points {
-            self.complex_samples.push_back(point);
+
+        // Fix the x axis so the bars sit in the middle of evenly sized slots,
+        // leaving the figure's own x limits untouched afterwards.
+        let (xlim, x_dynamic) = (self.config.xlim, self.x_dynamic);
+        self.config.xlim = Some([0.0, values.len() as f32]);
+        self.x_dynamic = false;
+        let vertices = self.normalize(&points, false);
+        let mut config = self.config.clone();
+        self.config.xlim = xlim;
+        self.x_dynamic = x_dynamic;
+
+        config.plot_type = PlotType::Bar;
+        config.categories = Some(labels.to_vec());
+        self.window.draw(&vertices, &[], &config);
+    }
+
+    /// Takes a slice of complex samples and draws them onto the plot. Samples
+    /// received from the stream are appended to the queue and any samples
+    /// exceeding the queue size are evicted according to `eviction`. Line
+    /// plots connect the samples from oldest to newest.
+    pub fn plot_complex_stream<T>(&mut self, points: &[Complex<T>])
+    where
+        T: Into<f32> + Copy,
+    {
+        if self.queue_size == 0 {
+            return;
+        }
+        if !self.paused {
+            enqueue(
+                &mut self.complex_samples,
+                points
+                    .iter()
+                    .map(|x| Complex::new(x.re.into(), x.im.into())),
+                self.queue_size,
+                self.config.eviction,
+            );
         }
 
-        let points: Vec<Point2<f32>> = self
-            .complex_samples
+        let view = self.view(self.complex_samples.len());
+        let points: Vec<Point2<f32>> = self.complex_samples[view]
             .iter()
             .map(|x| Point2::new(x.re, x.im))
             .collect();
-        let vertices = self.normalize(&points);
-        self.window.draw(&vertices, &self.config);
+        let vertices = self.normalize(&points, !self.config.independent_complex_axes);
+        self.window.draw(&vertices, &[], &self.config);
     }
 
     /// Takes a slice of complex samples and draws them onto the plot. This
-    /// overrides any existing samples in the queue.
+    /// overrides any existing samples in the queue. Line plots connect the
+    /// samples in the order given, tracing the trajectory through the IQ
+    /// plane.
     pub fn plot_complex<T>(&mut self, coords: &[Complex<T>])
     where
         T: Into<f32> + Copy,
     {
+        let mut points = std::mem::take(&mut self.scratch_points);
+        points.clear();
+        points.extend(
+            coords
+                .iter()
+                .map(|pt| Point2::new(pt.re.into(), pt.im.into())),
+        );
+        self.plot_accumulating(&points, |figure, points| {
+            let joint = !figure.config.independent_complex_axes;
+            let vertices = figure.normalize(points, joint);
+            figure.window.draw(&vertices, &[], &figure.config);
+            figure.scratch_vertices = vertices;
+        });
+        self.scratch_points = points;
+    }
+
+    /// Draws the magnitude of the complex samples against the sample index,
+    /// coloring each point by its phase along the cyclic `Colormap::Hsv`, so
+    /// both parts of each sample show in a single trace. This overrides any
+    /// samples in the queue.
+    pub fn plot_complex_mag_phase<T>(&mut self, coords: &[Complex<T>])
+    where
+        T: Into<f32> + Copy,
+    {
+        let coords: Vec<Complex<f32>> = coords
+            .iter()
+            .map(|x| Complex::new(x.re.into(), x.im.into()))
+            .collect();
         let points: Vec<Point2<f32>> = coords
             .iter()
-            .map(|pt| Point2::new(pt.re.into(), pt.im.into()))
+            .enumerate()
+            .map(|(i, x)| Point2::new(i as f32, x.norm()))
             .collect();
+        let colors = coords
+            .iter()
+            .map(|x| Colormap::Hsv.sample((x.arg() + PI) / (2.0 * PI)))
+            .collect();
+        self.update_stats(&points);
+        let vertices = self.normalize(&points, false);
+        let mut config = self.config.clone();
+        config.point_colors = Some(colors);
+        self.window.draw(&vertices, &[], &config);
+    }
+
+    /// Draws the in-phase and quadrature parts of the complex samples as two
+    /// lines against the sample index, the I trace in `color` and the Q
+    /// trace in `q_color`. Both traces share the same limits. This overrides
+    /// any samples in the queue.
+    pub fn plot_complex_iq_time<T>(&mut self, coords: &[Complex<T>])
+    where
+        T: Into<f32> + Copy,
+    {
+        let (i_points, q_points): (Vec<Point2<f32>>, Vec<Point2<f32>>) = coords
+            .iter()
+            .enumerate()
+            .map(|(n, x)| {
+                let n = n as f32;
+                (Point2::new(n, x.re.into()), Point2::new(n, x.im.into()))
+            })
+            .unzip();
+
+        let all_points: Vec<Point2<f32>> =
+            i_points.iter().chain(q_points.iter()).cloned().collect();
+        let (xlim, ylim) = self.update_limits(&all_points, false);
+        let overlays = [
+            Overlay {
+                vertices: self.map_to_plot(&i_points, xlim, ylim),
+                color: self.config.color,
+            },
+            Overlay {
+                vertices: map_points(&q_points, xlim, ylim, &self.config).0,
+                color: self.config.q_color.into(),
+            },
+        ];
+        self.window.draw(&[], &overlays, &self.config);
+    }
+
+    /// Takes a slice of double precision complex samples and draws them onto
+    /// the plot, as with `plot_complex`. Samples are converted to single
+    /// precision for drawing, so any precision beyond that of an `f32` is
+    /// lost.
+    pub fn plot_complex_f64(&mut self, coords: &[Complex<f64>]) {
+        let coords: Vec<Complex<f32>> = coords
+            .iter()
+            .map(|x| Complex::new(x.re as f32, x.im as f32))
+            .collect();
+        self.plot_complex(&coords);
+    }
+
+    /// Takes a slice of double precision complex samples and adds them to
+    /// the queue, as with `plot_complex_stream`. Samples are converted to
+    /// single precision before being queued, so any precision beyond that of
+    /// an `f32` is lost.
+    pub fn plot_complex_stream_f64(&mut self, points: &[Complex<f64>]) {
+        let points: Vec<Complex<f32>> = points
+            .iter()
+            .map(|x| Complex::new(x.re as f32, x.im as f32))
+            .collect();
+        self.plot_complex_stream(&points);
+    }
+
+    /// Renders the points offscreen at the given size and returns the pixels
+    /// as RGBA bytes, top row first, without touching the window or the
+    /// samples in the queue. Rendering is deterministic for a given figure
+    /// configuration, set of points and size, which makes this suitable for
+    /// golden-image tests alongside `diff_rgba`.
+    pub fn render_to_rgba<T>(&mut self, width: u32, height: u32, points: &[(T, T)]) -> Vec<u8>
+    where
+        T: Into<f32> + Copy,
+    {
+        let points: Vec<Point2<f32>> = points
+            .iter()
+            .map(|pt| Point2::new(pt.0.into(), pt.1.into()))
+            .collect();
+        let vertices = self.normalize(&points, false);
+        self.window
+            .render_to_rgba(width, height, &vertices, &[], &self.config)
+    }
+
+    /// Returns the figure's configuration with the limits the last draw was
+    /// made with, for drawing the grid and labels to match it.
+    fn drawn_config(&self) -> FigureConfig<'a> {
+        let mut config = self.config.clone();
+        if let Some((xlim, ylim)) = self.visible {
+            config.xlim = Some(xlim);
+            config.ylim = Some(ylim);
+        }
+        config
+    }
+
+    /// Writes the last draw to an SVG file as it'd look in a window of the
+    /// given size, with the grid, ticks, labels and data as vector shapes and
+    /// text rather than pixels. The ticks follow the limits the draw was made
+    /// with, but the FPS counter isn't included. Overlays are only included
+    /// when `keep_last_draw` is set; otherwise the points of the last draw
+    /// are written on their own.
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P, width: u32, height: u32) -> io::Result<()> {
+        let config = self.drawn_config();
+        let mapped;
+        let (vertices, overlays) = match (self.window.last_draw(), self.visible) {
+            (Some(last_draw), _) => last_draw,
+            (None, Some((xlim, ylim))) => {
+                mapped = map_points(&self.last_points, xlim, ylim, &self.config).0;
+                (mapped.as_slice(), &[][..])
+            }
+            (None, None) => (&[][..], &[][..]),
+        };
+        let svg = Window::render_svg((width, height), vertices, overlays, &config);
+        fs::write(path, svg)
+    }
+
+    /// Takes a slice of complex samples and plots their power spectrum in dB,
+    /// against normalized frequency in cycles per sample. This overrides any
+    /// samples in the queue.
+    pub fn plot_spectrum<T>(&mut self, samples: &[Complex<T>])
+    where
+        T: Into<f32> + Copy,
+    {
+        let points = self.spectrum_points(samples);
         self.plot(&points);
     }
 
+    /// As with `plot_spectrum`, but also keeps the highest power seen in each
+    /// frequency bin across calls and draws it as a second trace in
+    /// `max_hold_color`. The held maximum is reset whenever the number of
+    /// samples changes, or by calling `reset_max_hold`.
+    pub fn plot_spectrum_maxhold<T>(&mut self, samples: &[Complex<T>])
+    where
+        T: Into<f32> + Copy,
+    {
+        let points = self.spectrum_points(samples);
+        if self.max_hold.len() != points.len() {
+            self.max_hold = points.iter().map(|pt| pt.y).collect();
+        }
+        for (held, pt) in self.max_hold.iter_mut().zip(points.iter()) {
+            *held = held.max(pt.y);
+        }
+        let max_hold: Vec<Point2<f32>> = points
+            .iter()
+            .zip(self.max_hold.iter())
+            .map(|(pt, held)| Point2::new(pt.x, *held))
+            .collect();
+
+        // Autoscale over both traces so the held maximum stays in view.
+        let all_points: Vec<Point2<f32>> = points.iter().chain(max_hold.iter()).cloned().collect();
+        let (xlim, ylim) = self.update_limits(&all_points, false);
+        let vertices = self.map_to_plot(&points, xlim, ylim);
+        let overlay = Overlay {
+            vertices: map_points(&max_hold, xlim, ylim, &self.config).0,
+            color: self.config.max_hold_color.into(),
+        };
+        self.window.draw(&vertices, &[overlay], &self.config);
+    }
+
+    /// Clears the maximum held by `plot_spectrum_maxhold`.
+    pub fn reset_max_hold(&mut self) {
+        self.max_hold.clear();
+    }
+
+    /// Computes the power spectrum of the samples as points to plot.
+    /// The window coefficients are kept between calls and only recomputed
+    /// when the window or the number of samples changes.
+    fn spectrum_points<T>(&mut self, samples: &[Complex<T>]) -> Vec<Point2<f32>>
+    where
+        T: Into<f32> + Copy,
+    {
+        let samples: Vec<Complex<f32>> = samples
+            .iter()
+            .map(|x| Complex::new(x.re.into(), x.im.into()))
+            .collect();
+        let window = self.config.spectrum_window;
+        let stale = match &self.window_coefficients {
+            Some((cached, coefficients)) => {
+                *cached != window || coefficients.len() != samples.len()
+            }
+            None => true,
+        };
+        if stale {
+            self.window_coefficients = Some((window, window.coefficients(samples.len())));
+        }
+        let (_, coefficients) = self.window_coefficients.as_ref().unwrap();
+        spectrum::power_spectrum_db(&samples, coefficients)
+            .into_iter()
+            .map(|(freq, power)| Point2::new(freq, power))
+            .collect()
+    }
+
+    /// Plots a batch of data from a `DataSource` using the plot method that
+    /// matches its kind.
+    pub fn plot_data(&mut self, data: PlotData) {
+        match data {
+            PlotData::Real(samples) => self.plot_stream(&samples),
+            PlotData::Complex(samples) => self.plot_complex_stream(&samples),
+            PlotData::Xy(points) => self.plot_xy(&points),
+            PlotData::Redraw => self.redraw(),
+        }
+    }
+
+    /// Hijacks the current thread to plot data pulled from the source each
+    /// frame, until either the source is exhausted or the window is closed.
+    /// With `Eviction::Block`, samples that don't fit in the queue are held
+    /// back and nothing more is pulled from the source until they fit.
+    pub fn run_source(&mut self, mut source: impl DataSource) {
+        let mut held = None;
+        while !self.should_close_window() {
+            let data = match held.take() {
+                Some(data) => data,
+                None => match source.next() {
+                    // Nothing new, so only handle window events.
+                    Some(PlotData::Redraw) => continue,
+                    Some(data) => data,
+                    None => break,
+                },
+            };
+            held = self.plot_data_with_room(data);
+            if held.is_some() {
+                // Wait for room without spinning, still handling events.
+                std::thread::sleep(Duration::from_millis(16));
+            }
+        }
+    }
+
+    /// Plots as much of a batch as there's room for in the queue under
+    /// `Eviction::Block`, returning the rest. Otherwise, plots it all.
+    fn plot_data_with_room(&mut self, data: PlotData) -> Option<PlotData> {
+        let blocking =
+            matches!(self.config.eviction, Eviction::Block) && self.queue_size > 0 && !self.paused;
+        let (data, held) = match data {
+            PlotData::Real(samples) if blocking => {
+                let room = self.queue_size.saturating_sub(self.samples.len());
+                let (samples, held) = split_at_room(samples, room);
+                (samples.map(PlotData::Real), held.map(PlotData::Real))
+            }
+            PlotData::Complex(samples) if blocking => {
+                let room = self.queue_size.saturating_sub(self.complex_samples.len());
+                let (samples, held) = split_at_room(samples, room);
+                (samples.map(PlotData::Complex), held.map(PlotData::Complex))
+            }
+            data => (Some(data), None),
+        };
+        if let Some(data) = data {
+            self.plot_data(data);
+        }
+        held
+    }
+
+    /// Plays back frames of real samples at a steady `fps` frames per second,
+    /// drawing each with `plot_y`. Frames are paced by wall-clock time
+    /// against when playback started, so a slow frame is caught up on rather
+    /// than delaying every frame after it. Unlike a cap on the frame rate,
+    /// this aims for the target rate. A rate that isn't positive draws the
+    /// frames as fast as possible. Stops when the frames run out or the
+    /// window is closed.
+    pub fn animate(&mut self, frames: impl Iterator<Item = Vec<f32>>, fps: f32) {
+        let start = Instant::now();
+        for (i, frame) in frames.enumerate() {
+            if self.should_close_window() {
+                break;
+            }
+            if fps > 0.0 {
+                let due = start + Duration::from_secs_f32(i as f32 / fps);
+                let now = Instant::now();
+                if due > now {
+                    std::thread::sleep(due - now);
+                }
+            }
+            self.plot_y(&frame);
+        }
+    }
+
+    /// Hijacks the current thread to stream samples from the source, passing
+    /// each item it yields to `plot_stream` as one frame. Stops when the
+    /// source runs out or the window is closed. Events are handled before
+    /// each item is pulled, so a source that blocks isn't asked for more once
+    /// the window has been closed. Any iterator works as a source, such as
+    /// the chunks of a file or an endless generator.
+    pub fn drive(&mut self, mut source: impl Iterator<Item = Vec<f32>>) {
+        while !self.should_close_window() {
+            match source.next() {
+                Some(samples) => self.plot_stream(&samples),
+                None => break,
+            }
+        }
+    }
+
     /// Hijacks the current thread to run the plotting and event loop.
     pub fn display(figure: &mut Figure, mut plot_fn: impl FnMut(&mut Figure)) {
         while !figure.should_close_window() {
@@ -311,3 +2750,336 @@ impl<'a> Figure<'a> {
         }
     }
 }
+
+/// Sets the limits of a linked axis, if there is one, marking them as seen.
+fn publish(link: &mut Option<(SharedAxis, u64)>, lims: [f32; 2]) {
+    if let Some((axis, seen)) = link {
+        axis.set(lims);
+        *seen = axis.generation();
+    }
+}
+
+/// Adds points to those accumulated so far, evicting points according to the
+/// eviction policy to keep at most `queue_size`. Returns whether the
+/// accumulated points should be drawn. With a queue size of zero there's
+/// nowhere to keep points, so nothing is added and the new points should be
+/// drawn on their own.
+fn accumulate(
+    accumulated: &mut SliceDeque<Point2<f32>>,
+    points: &[Point2<f32>],
+    queue_size: usize,
+    eviction: Eviction,
+) -> bool {
+    if queue_size == 0 {
+        return false;
+    }
+    enqueue(accumulated, points.iter().copied(), queue_size, eviction);
+    true
+}
+
+/// Adds samples to a queue, keeping it at or under `queue_size` by evicting
+/// samples according to the eviction policy.
+fn enqueue<T>(
+    queue: &mut SliceDeque<T>,
+    samples: impl Iterator<Item = T>,
+    queue_size: usize,
+    eviction: Eviction,
+) {
+    match eviction {
+        Eviction::DropOldest => {
+            for sample in samples {
+                queue.push_back(sample);
+            }
+            while queue.len() > queue_size {
+                queue.pop_front();
+            }
+        }
+        Eviction::DropNewest | Eviction::Block => {
+            let room = queue_size.saturating_sub(queue.len());
+            for sample in samples.take(room) {
+                queue.push_back(sample);
+            }
+        }
+    }
+}
+
+/// Splits a batch of samples into those that fit in the room left in a queue
+/// and those that don't, with None for either part that's empty.
+fn split_at_room<T>(mut samples: Vec<T>, room: usize) -> (Option<Vec<T>>, Option<Vec<T>>) {
+    let held = samples.split_off(room.min(samples.len()));
+    let non_empty = |samples: Vec<T>| Some(samples).filter(|samples| !samples.is_empty());
+    (non_empty(samples), non_empty(held))
+}
+
+/// Converts two corners of a selection on the plot area into the limits of
+/// the data inside it, given the limits the plot was drawn with. The new
+/// limits run in the same direction as the old ones, so an inverted axis
+/// stays inverted. Returns None if the selection has no width or height.
+fn selection_limits(
+    start: [f32; 2],
+    end: [f32; 2],
+    xlim: [f32; 2],
+    ylim: [f32; 2],
+    extent: f32,
+) -> Option<([f32; 2], [f32; 2])> {
+    let span = |a: f32, b: f32, lims: [f32; 2]| {
+        let a = utils::denormalize_value(a, lims, extent);
+        let b = utils::denormalize_value(b, lims, extent);
+        if a == b {
+            None
+        } else if lims[0] > lims[1] {
+            Some([a.max(b), a.min(b)])
+        } else {
+            Some([a.min(b), a.max(b)])
+        }
+    };
+    Some((span(start[0], end[0], xlim)?, span(start[1], end[1], ylim)?))
+}
+
+/// Picks `count` points spread evenly across the points, keeping the first.
+fn decimate<T: Copy>(points: &[T], count: usize) -> Vec<T> {
+    (0..count)
+        .map(|i| points[i * points.len() / count])
+        .collect()
+}
+
+/// Blends newly autoscaled limits with the previous limits, keeping the given
+/// fraction of the previous limits.
+fn smooth_limits(previous: Option<[f32; 2]>, next: [f32; 2], smoothing: f32) -> [f32; 2] {
+    match previous {
+        Some([min, max]) if smoothing > 0.0 => [
+            smoothing * min + (1.0 - smoothing) * next[0],
+            smoothing * max + (1.0 - smoothing) * next[1],
+        ],
+        _ => next,
+    }
+}
+
+/// Maps points onto the plot area as a figure with the given configuration
+/// would, without a window or any GL context. Axes without limits are fit to
+/// the points, and points outside the limits are handled according to
+/// `clip_mode`. Unlike a figure, nothing is remembered between calls, so
+/// limit smoothing doesn't apply.
+pub fn compute_vertices(config: &FigureConfig, points: &[(f32, f32)]) -> Vec<Vertex> {
+    let points: Vec<Point2<f32>> = points.iter().map(|pt| Point2::new(pt.0, pt.1)).collect();
+    let xlim = config.xlim.unwrap_or_else(|| utils::calc_xlims(&points));
+    let ylim = config.ylim.unwrap_or_else(|| utils::calc_ylims(&points));
+    map_points(&points, xlim, ylim, config).0
+}
+
+/// Maps the points onto the plot area using the given limits. Also returns
+/// how many points fell outside the limits.
+fn map_points(
+    points: &[Point2<f32>],
+    xlim: [f32; 2],
+    ylim: [f32; 2],
+    config: &FigureConfig,
+) -> (Vec<Vertex>, usize) {
+    let mut vertices = vec![];
+    let clipped = map_points_into(points, xlim, ylim, config, &mut vertices);
+    (vertices, clipped)
+}
+
+/// Maps the points onto the plot area as with `map_points`, appending the
+/// vertices to an existing vector so its allocation can be reused. Returns
+/// how many points fell outside the limits.
+fn map_points_into(
+    points: &[Point2<f32>],
+    xlim: [f32; 2],
+    ylim: [f32; 2],
+    config: &FigureConfig,
+    vertices: &mut Vec<Vertex>,
+) -> usize {
+    let [min_x, max_x] = xlim;
+    let [min_y, max_y] = ylim;
+    let mut clipped = 0;
+    for point in points {
+        // Points with missing values, such as NaN gaps, aren't drawn at all.
+        if !point.x.is_finite() || !point.y.is_finite() {
+            continue;
+        }
+        let outside = point.x > max_x || point.x < min_x || point.y > max_y || point.y < min_y;
+        if outside {
+            clipped += 1;
+        }
+        let (x, y) = match config.clip_mode {
+            ClipMode::Drop if outside => continue,
+            ClipMode::Clip => (point.x.max(min_x).min(max_x), point.y.max(min_y).min(max_y)),
+            _ => (point.x, point.y),
+        };
+        let (x, y) = utils::normalize_point((x, y), xlim, ylim, config.layout.plot_extent);
+        vertices.push(Vertex::new(x, y, config.color));
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empties the queue into a vector. Dropping a non-empty `SliceDeque`
+    /// trips the standard library's debug checks on unchecked indexing, so
+    /// the queues are emptied one item at a time instead.
+    fn drain<T>(mut queue: SliceDeque<T>) -> Vec<T> {
+        let mut items = vec![];
+        while let Some(item) = queue.pop_front() {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn enqueue_drop_oldest_keeps_last_items() {
+        let mut queue = SliceDeque::new();
+        enqueue(&mut queue, 0..5000, 1000, Eviction::DropOldest);
+        assert_eq!(drain(queue), (4000..5000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn zero_queue_size_keeps_nothing() {
+        for eviction in [Eviction::DropOldest, Eviction::DropNewest, Eviction::Block] {
+            let mut queue = SliceDeque::new();
+            enqueue(&mut queue, 0..10, 0, eviction);
+            assert!(queue.is_empty());
+
+            let mut accumulated = SliceDeque::new();
+            let points = [Point2::new(0.0, 1.0), Point2::new(1.0, 2.0)];
+            assert!(!accumulate(&mut accumulated, &points, 0, eviction));
+            assert!(accumulated.is_empty());
+        }
+    }
+
+    #[test]
+    fn accumulate_caps_at_queue_size() {
+        let mut accumulated = SliceDeque::new();
+        let points: Vec<Point2<f32>> = (0..5).map(|i| Point2::new(i as f32, 0.0)).collect();
+        assert!(accumulate(
+            &mut accumulated,
+            &points,
+            3,
+            Eviction::DropOldest
+        ));
+        assert!(accumulate(
+            &mut accumulated,
+            &points[..1],
+            3,
+            Eviction::DropOldest
+        ));
+        let xs: Vec<f32> = drain(accumulated).iter().map(|pt| pt.x).collect();
+        assert_eq!(xs, vec![3.0, 4.0, 0.0]);
+    }
+
+    #[test]
+    fn enqueue_drop_newest_keeps_first_items() {
+        let mut queue = SliceDeque::new();
+        enqueue(&mut queue, 0..5000, 1000, Eviction::DropNewest);
+        assert_eq!(drain(queue), (0..1000).collect::<Vec<_>>());
+    }
+
+    /// Times the work done for each draw of an accumulating plot, short of
+    /// tessellating it. This is a benchmark rather than a check, so it's
+    /// ignored by default. Run it with
+    /// `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn accumulating_draw_timing() {
+        let config = FigureConfig::default();
+        let frame: Vec<Point2<f32>> = (0..1000)
+            .map(|i| Point2::new(i as f32, (i as f32).sin()))
+            .collect();
+        let mut accumulated = SliceDeque::new();
+        let mut vertices = vec![];
+        let frames = 1000;
+        let start = Instant::now();
+        for _ in 0..frames {
+            accumulate(&mut accumulated, &frame, 100_000, Eviction::DropOldest);
+            assert!(utils::calc_stats(accumulated.iter().map(|pt| pt.y)).is_some());
+            vertices.clear();
+            map_points_into(
+                &accumulated,
+                [0.0, 1000.0],
+                [-1.0, 1.0],
+                &config,
+                &mut vertices,
+            );
+        }
+        println!(
+            "{:?} per draw of {} points",
+            start.elapsed() / frames,
+            accumulated.len()
+        );
+        drain(accumulated);
+    }
+
+    /// Times offscreen renders of a long line drawn directly as a GL line
+    /// strip. Needs a display, and is a benchmark rather than a check, so
+    /// it's ignored by default. Run it with
+    /// `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn fast_line_render_timing() {
+        let config = FigureConfig {
+            plot_type: PlotType::Line,
+            fast_render: true,
+            ..Default::default()
+        };
+        let mut figure = Figure::new_with_config(config, 0);
+        let points: Vec<(f32, f32)> = (0..100_000)
+            .map(|i| (i as f32, (i as f32 / 100.0).sin()))
+            .collect();
+        let frames = 100;
+        let start = Instant::now();
+        for _ in 0..frames {
+            assert!(!figure.render_to_rgba(800, 600, &points).is_empty());
+        }
+        println!(
+            "{:?} per render of {} points",
+            start.elapsed() / frames,
+            points.len()
+        );
+    }
+
+    #[test]
+    fn enqueue_block_stops_accepting_when_full() {
+        let mut queue = SliceDeque::new();
+        enqueue(&mut queue, 0..5000, 1000, Eviction::Block);
+        enqueue(&mut queue, 5000..6000, 1000, Eviction::Block);
+        assert_eq!(drain(queue), (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_at_room_holds_back_the_rest() {
+        assert_eq!(
+            split_at_room(vec![1, 2, 3], 2),
+            (Some(vec![1, 2]), Some(vec![3]))
+        );
+        assert_eq!(split_at_room(vec![1, 2, 3], 5), (Some(vec![1, 2, 3]), None));
+        assert_eq!(split_at_room(vec![1, 2, 3], 0), (None, Some(vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn selection_limits_cover_the_selected_data() {
+        let limits = selection_limits([0.0, 0.5], [-0.5, -0.5], [0.0, 10.0], [0.0, 100.0], 1.0);
+        assert_eq!(limits, Some(([2.5, 5.0], [25.0, 75.0])));
+    }
+
+    #[test]
+    fn selection_limits_keep_inverted_axes() {
+        let limits = selection_limits([0.0, 0.5], [-0.5, -0.5], [0.0, 10.0], [100.0, 0.0], 1.0);
+        assert_eq!(limits, Some(([2.5, 5.0], [75.0, 25.0])));
+    }
+
+    #[test]
+    fn empty_selection_is_ignored() {
+        let xlim = [0.0, 10.0];
+        let ylim = [0.0, 100.0];
+        assert_eq!(
+            selection_limits([0.2, 0.0], [0.2, 0.5], xlim, ylim, 1.0),
+            None
+        );
+        assert_eq!(
+            selection_limits([0.0, 0.4], [0.5, 0.4], xlim, ylim, 1.0),
+            None
+        );
+    }
+}
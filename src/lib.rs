@@ -2,8 +2,26 @@
 //! periodically and the plot automatically updates.
 //!
 
+#[cfg(feature = "async")]
+mod channel;
+mod colormap;
 mod figure;
+mod snapshot;
+mod source;
+mod spectrum;
 mod utils;
 mod window;
 
-pub use figure::{Figure, FigureConfig, PlotType};
+#[cfg(feature = "async")]
+pub use channel::{channel, PlotReceiver, PlotSender};
+pub use colormap::{Color, ColorCycle, Colormap};
+pub use figure::{
+    compute_vertices, ClipMode, ColorThreshold, Condition, Corner, Eviction, Figure, FigureConfig,
+    Layer, LayoutConfig, LineCap, LineJoin, MarkerShape, NumberFormat, PathStyle, PlotType,
+    Prefill, SharedAxis,
+};
+pub use snapshot::diff_rgba;
+pub use source::{DataSource, FileFormat, FileSource, PlotData};
+pub use spectrum::WindowFunction;
+pub use utils::{nice_ticks, normalize_point};
+pub use window::{FrameInfo, Vertex};
@@ -0,0 +1,19 @@
+/// Compares two RGBA images of the same size, such as those produced by
+/// `Figure::render_to_rgba`, returning the number of pixels where any channel
+/// differs by more than `tolerance`. Returns None if the images aren't the
+/// same size.
+pub fn diff_rgba(expected: &[u8], actual: &[u8], tolerance: u8) -> Option<usize> {
+    if expected.len() != actual.len() || !expected.len().is_multiple_of(4) {
+        return None;
+    }
+    let differing = expected
+        .chunks(4)
+        .zip(actual.chunks(4))
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(b.iter())
+                .any(|(x, y)| (i16::from(*x) - i16::from(*y)).abs() > i16::from(tolerance))
+        })
+        .count();
+    Some(differing)
+}
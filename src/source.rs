@@ -0,0 +1,194 @@
+use num::Complex;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A batch of data produced by a `DataSource`.
+#[derive(Clone, Debug)]
+pub enum PlotData {
+    /// Real samples to append to the stream, as with `Figure::plot_stream`.
+    Real(Vec<f32>),
+
+    /// Complex samples to append to the stream, as with
+    /// `Figure::plot_complex_stream`.
+    Complex(Vec<Complex<f32>>),
+
+    /// Points that replace anything currently plotted, as with
+    /// `Figure::plot_xy`.
+    Xy(Vec<(f32, f32)>),
+
+    /// No new data yet. Lets a source that's waiting on data keep the window
+    /// responsive: `Figure::run_source` only handles window events for it,
+    /// while `Figure::plot_data` draws the plot again as with
+    /// `Figure::redraw`.
+    Redraw,
+}
+
+/// A producer of data to be plotted, such as a file or socket reader. Use
+/// with `Figure::run_source` to plot everything the source produces.
+pub trait DataSource {
+    /// Returns the next batch of data to plot, or None once the source has
+    /// been exhausted.
+    fn next(&mut self) -> Option<PlotData>;
+}
+
+/// The layout of the values in a file read by a `FileSource`.
+#[derive(Copy, Clone, Debug)]
+pub enum FileFormat {
+    /// Little-endian 32-bit floats.
+    BinaryReal,
+
+    /// Interleaved little-endian 32-bit floats, real part first.
+    BinaryComplex,
+
+    /// Numbers in text, separated by commas, whitespace or newlines. Fields
+    /// that aren't numbers, such as headers, are skipped.
+    CsvReal,
+
+    /// As with `CsvReal`, with the numbers interleaved real part first. A
+    /// file with `re,im` on each line is read as one sample per line.
+    CsvComplex,
+}
+
+impl FileFormat {
+    fn is_complex(self) -> bool {
+        match self {
+            FileFormat::BinaryComplex | FileFormat::CsvComplex => true,
+            FileFormat::BinaryReal | FileFormat::CsvReal => false,
+        }
+    }
+}
+
+/// Replays samples recorded to a file in chunks, optionally at a fixed rate.
+pub struct FileSource {
+    reader: BufReader<File>,
+    format: FileFormat,
+
+    /// The number of samples produced by each call to `next`.
+    chunk: usize,
+
+    /// The minimum time between chunks. If set to None, chunks are produced
+    /// as fast as they're requested.
+    interval: Option<Duration>,
+
+    /// When the last chunk was produced.
+    last: Option<Instant>,
+
+    /// Values parsed from a CSV file that haven't been produced yet.
+    pending: VecDeque<f32>,
+}
+
+impl FileSource {
+    /// Opens a file to be read `chunk` samples at a time.
+    pub fn new<P: AsRef<Path>>(path: P, format: FileFormat, chunk: usize) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(File::open(path)?),
+            format,
+            chunk,
+            interval: None,
+            last: None,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Limits how many chunks are produced per second, to replay the file at
+    /// roughly the speed it was recorded. A rate that's zero, negative, not
+    /// finite or too small for its interval to fit in a `Duration` leaves the
+    /// chunks unthrottled.
+    pub fn rate(mut self, chunks_per_second: f32) -> Self {
+        self.interval = chunk_interval(chunks_per_second);
+        self
+    }
+
+    /// Reads up to `count` values from the file.
+    fn read_values(&mut self, count: usize) -> io::Result<Vec<f32>> {
+        match self.format {
+            FileFormat::BinaryReal | FileFormat::BinaryComplex => {
+                let mut bytes = vec![];
+                (&mut self.reader)
+                    .take(count as u64 * 4)
+                    .read_to_end(&mut bytes)?;
+                Ok(bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect())
+            }
+            FileFormat::CsvReal | FileFormat::CsvComplex => {
+                let mut line = String::new();
+                while self.pending.len() < count {
+                    line.clear();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        break;
+                    }
+                    let values = line
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter_map(|field| field.parse::<f32>().ok());
+                    self.pending.extend(values);
+                }
+                let count = count.min(self.pending.len());
+                Ok(self.pending.drain(..count).collect())
+            }
+        }
+    }
+}
+
+impl DataSource for FileSource {
+    fn next(&mut self) -> Option<PlotData> {
+        if let (Some(interval), Some(last)) = (self.interval, self.last) {
+            let elapsed = last.elapsed();
+            if elapsed < interval {
+                thread::sleep(interval - elapsed);
+            }
+        }
+        self.last = Some(Instant::now());
+
+        let data = if self.format.is_complex() {
+            let values = self.read_values(self.chunk * 2).ok()?;
+            let samples: Vec<Complex<f32>> = values
+                .chunks_exact(2)
+                .map(|pair| Complex::new(pair[0], pair[1]))
+                .collect();
+            if samples.is_empty() {
+                return None;
+            }
+            PlotData::Complex(samples)
+        } else {
+            let values = self.read_values(self.chunk).ok()?;
+            if values.is_empty() {
+                return None;
+            }
+            PlotData::Real(values)
+        };
+        Some(data)
+    }
+}
+
+/// Returns the time between chunks produced at the given rate, or None if
+/// the rate can't be throttled to.
+fn chunk_interval(chunks_per_second: f32) -> Option<Duration> {
+    if chunks_per_second > 0.0 && chunks_per_second.is_finite() {
+        Duration::try_from_secs_f32(1.0 / chunks_per_second).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_interval_follows_the_rate() {
+        assert_eq!(chunk_interval(4.0), Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn invalid_rates_are_unthrottled() {
+        for rate in [0.0, -2.0, f32::NAN, f32::INFINITY, f32::MIN_POSITIVE] {
+            assert_eq!(chunk_interval(rate), None);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use num::Complex;
+use std::f32::consts::PI;
+
+/// A window applied to samples before taking their spectrum. Tapering the
+/// ends of the samples reduces the leakage of strong tones into other bins,
+/// at the cost of a wider main lobe around each tone.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum WindowFunction {
+    /// The samples are used as-is. This gives the narrowest main lobe but
+    /// the most leakage.
+    #[default]
+    Rectangular,
+
+    /// A raised cosine reaching zero at both ends. A good general-purpose
+    /// choice.
+    Hann,
+
+    /// A raised cosine that stops short of zero, cancelling the nearest side
+    /// lobe.
+    Hamming,
+
+    /// A sum of cosines with much lower side lobes than Hann or Hamming, but
+    /// a wider main lobe.
+    Blackman,
+}
+
+impl WindowFunction {
+    /// Returns the coefficients of the window for the given number of
+    /// samples.
+    pub fn coefficients(&self, n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| {
+                let phase = 2.0 * PI * i as f32 / n as f32;
+                match self {
+                    WindowFunction::Rectangular => 1.0,
+                    WindowFunction::Hann => 0.5 - 0.5 * phase.cos(),
+                    WindowFunction::Hamming => 0.54 - 0.46 * phase.cos(),
+                    WindowFunction::Blackman => {
+                        0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Computes the discrete Fourier transform of the samples with a radix-2
+/// FFT. Samples whose length isn't a power of two are padded with zeros up
+/// to the next one, so the result has that many bins, spaced more finely
+/// than the samples alone would give.
+pub fn fft(samples: &[Complex<f32>]) -> Vec<Complex<f32>> {
+    let n = samples.len().next_power_of_two();
+    if n < 2 {
+        return samples.to_vec();
+    }
+
+    // Reorder the samples by bit-reversed index so the butterflies can be
+    // done in place.
+    let bits = n.trailing_zeros();
+    let mut out: Vec<Complex<f32>> = (0..n)
+        .map(|i| {
+            let index = i.reverse_bits() >> (usize::BITS - bits);
+            samples.get(index).copied().unwrap_or_default()
+        })
+        .collect();
+
+    let mut size = 2;
+    while size <= n {
+        let half = size / 2;
+        let step = -2.0 * PI / size as f32;
+        for start in (0..n).step_by(size) {
+            for k in 0..half {
+                let angle = step * k as f32;
+                let twiddle = Complex::new(angle.cos(), angle.sin());
+                let even = out[start + k];
+                let odd = out[start + k + half] * twiddle;
+                out[start + k] = even + odd;
+                out[start + k + half] = even - odd;
+            }
+        }
+        size *= 2;
+    }
+    out
+}
+
+/// Computes the power spectrum of the samples in dB after multiplying them by
+/// the window coefficients, with zero frequency in the middle. The power is
+/// scaled by the gain of the window, so a tone reads the same whichever
+/// window is used. Returns pairs of normalized frequency, in cycles per
+/// sample, and power, with a pair for each bin of the padded FFT.
+pub fn power_spectrum_db(samples: &[Complex<f32>], window: &[f32]) -> Vec<(f32, f32)> {
+    let windowed: Vec<Complex<f32>> = samples
+        .iter()
+        .zip(window.iter())
+        .map(|(x, w)| x * w)
+        .collect();
+    let gain: f32 = window.iter().sum();
+    let spectrum = fft(&windowed);
+    let n = spectrum.len();
+    let (positive, negative) = spectrum.split_at(n.div_ceil(2));
+    negative
+        .iter()
+        .chain(positive.iter())
+        .enumerate()
+        .map(|(i, x)| {
+            let freq = (i as f32 - (n / 2) as f32) / n as f32;
+            let power = (x.norm_sqr() / (gain * gain)).max(1e-20);
+            (freq, 10.0 * power.log10())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_periodic_symmetry(coefficients: &[f32]) {
+        let n = coefficients.len();
+        for i in 1..n {
+            assert!((coefficients[i] - coefficients[n - i]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn hann_endpoints_and_symmetry() {
+        let coefficients = WindowFunction::Hann.coefficients(16);
+        assert!(coefficients[0].abs() < 1e-6);
+        assert!((coefficients[8] - 1.0).abs() < 1e-6);
+        assert_periodic_symmetry(&coefficients);
+    }
+
+    #[test]
+    fn hamming_endpoints_and_symmetry() {
+        let coefficients = WindowFunction::Hamming.coefficients(16);
+        assert!((coefficients[0] - 0.08).abs() < 1e-6);
+        assert!((coefficients[8] - 1.0).abs() < 1e-6);
+        assert_periodic_symmetry(&coefficients);
+    }
+
+    /// Returns the normalized frequencies of the bins within 100 dB of the
+    /// peak, after checking the peak is at 0 dB.
+    fn main_lobe(window: WindowFunction) -> Vec<f32> {
+        let n = 64;
+        let tone: Vec<Complex<f32>> = (0..n)
+            .map(|i| {
+                let phase = 2.0 * PI * 8.0 * i as f32 / n as f32;
+                Complex::new(phase.cos(), phase.sin())
+            })
+            .collect();
+        let spectrum = power_spectrum_db(&tone, &window.coefficients(n));
+        let peak = spectrum.iter().map(|(_, p)| *p).fold(f32::MIN, f32::max);
+        assert!(peak.abs() < 1e-3, "peak of {} dB", peak);
+        spectrum
+            .iter()
+            .filter(|(_, power)| *power > -100.0)
+            .map(|(freq, _)| *freq)
+            .collect()
+    }
+
+    #[test]
+    fn padded_tone_peaks_in_the_nearest_bin() {
+        let n = 48;
+        let freq = 5.0 / n as f32;
+        let tone: Vec<Complex<f32>> = (0..n)
+            .map(|i| {
+                let phase = 2.0 * PI * freq * i as f32;
+                Complex::new(phase.cos(), phase.sin())
+            })
+            .collect();
+        let spectrum = power_spectrum_db(&tone, &WindowFunction::Rectangular.coefficients(n));
+        assert_eq!(spectrum.len(), 64);
+        let (peak, _) =
+            spectrum
+                .iter()
+                .copied()
+                .fold((0.0, f32::MIN), |a, b| if b.1 > a.1 { b } else { a });
+        assert!((peak - freq).abs() <= 0.5 / 64.0, "peak at {}", peak);
+        assert_eq!(peak, 7.0 / 64.0);
+    }
+
+    #[test]
+    fn tone_main_lobe_width() {
+        assert_eq!(main_lobe(WindowFunction::Rectangular), vec![0.125]);
+        assert_eq!(
+            main_lobe(WindowFunction::Hann),
+            vec![7.0 / 64.0, 0.125, 9.0 / 64.0]
+        );
+        assert_eq!(main_lobe(WindowFunction::Blackman).len(), 5);
+    }
+}
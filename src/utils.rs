@@ -1,15 +1,50 @@
 use cgmath::Point2;
 
+/// Finds the min and max of the finite values, ignoring infinities and NaNs
+/// so that a single bad sample doesn't blow out the range. Returns [0.0, 1.0]
+/// if there are no finite values.
 fn calc_min_max(points: &[f32]) -> [f32; 2] {
-    let min_val = points
-        .iter()
-        .min_by(|x, y| x.partial_cmp(y).unwrap())
-        .unwrap();
-    let max_val = points
-        .iter()
-        .max_by(|x, y| x.partial_cmp(y).unwrap())
-        .unwrap();
-    [*min_val, *max_val]
+    let mut finite = points.iter().filter(|x| x.is_finite());
+    let first = match finite.next() {
+        Some(first) => *first,
+        None => return [0.0, 1.0],
+    };
+    finite.fold([first, first], |[min_val, max_val], x| {
+        [min_val.min(*x), max_val.max(*x)]
+    })
+}
+
+/// Summary statistics of a set of values.
+pub struct Stats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub rms: f32,
+}
+
+/// Calculates the min, max, mean and RMS of the finite values in a single
+/// pass, without collecting them. Returns None if there are no finite values.
+pub fn calc_stats(values: impl IntoIterator<Item = f32>) -> Option<Stats> {
+    let mut count = 0;
+    let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+    let (mut sum, mut sum_squares) = (0.0, 0.0);
+    for value in values.into_iter().filter(|x| x.is_finite()) {
+        count += 1;
+        min = min.min(value);
+        max = max.max(value);
+        sum += value;
+        sum_squares += value * value;
+    }
+    if count == 0 {
+        return None;
+    }
+    let count = count as f32;
+    Some(Stats {
+        min,
+        max,
+        mean: sum / count,
+        rms: (sum_squares / count).sqrt(),
+    })
 }
 
 pub fn calc_xlims(points: &[Point2<f32>]) -> [f32; 2] {
@@ -23,3 +58,166 @@ pub fn calc_ylims(points: &[Point2<f32>]) -> [f32; 2] {
     let ylims: [f32; 2] = calc_min_max(&y);
     ylims
 }
+
+/// Maps a value in data coordinates onto the plot area, given the min and max
+/// limits of the axis and half the width of the plot area.
+pub fn normalize_value(value: f32, lims: [f32; 2], extent: f32) -> f32 {
+    let [min, max] = lims;
+    let error: f32 = 0.0;
+    if (max - min).abs() > error {
+        2.0 * extent * (value - min) / (max - min) - extent
+    } else {
+        2.0 * extent * value - extent
+    }
+}
+
+/// Maps a point in data coordinates onto the plot area, given the limits of
+/// each axis and half the width of the plot area. This is the transform every
+/// drawn point goes through, with no other state involved. Points outside the
+/// limits map outside [-extent, extent] rather than being clamped. Inverted
+/// limits, where min is greater than max, flip the axis. If an axis's min and
+/// max are equal, values along it are scaled as if the limits were [0, 1].
+pub fn normalize_point(
+    (x, y): (f32, f32),
+    xlim: [f32; 2],
+    ylim: [f32; 2],
+    extent: f32,
+) -> (f32, f32) {
+    (
+        normalize_value(x, xlim, extent),
+        normalize_value(y, ylim, extent),
+    )
+}
+
+/// Maps a position on the plot area back to data coordinates, undoing
+/// `normalize_value`.
+pub fn denormalize_value(position: f32, lims: [f32; 2], extent: f32) -> f32 {
+    let [min, max] = lims;
+    let error: f32 = 0.0;
+    if (max - min).abs() > error {
+        (position + extent) * (max - min) / (2.0 * extent) + min
+    } else {
+        (position + extent) / (2.0 * extent)
+    }
+}
+
+/// Computes roughly `target` ticks between min and max, spaced at a "nice"
+/// interval of 1, 2 or 5 times a power of ten. These are the same ticks the
+/// plot uses with a tick target set, so annotations can be lined up with
+/// them. The ticks are in increasing order and all lie within the range,
+/// which may be given either way round. An empty or non-finite range, or a
+/// target under two, gives a single tick at min.
+pub fn nice_ticks(min: f32, max: f32, target: usize) -> Vec<f32> {
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    let range = max - min;
+    if !range.is_finite() || range <= 0.0 || target < 2 {
+        return vec![min];
+    }
+    let raw_step = range / (target - 1) as f32;
+    let magnitude = 10f32.powf(raw_step.log10().floor());
+    let residual = raw_step / magnitude;
+
+    // Pick whichever nice interval is closest to the raw step on a log scale.
+    let step = magnitude
+        * if residual < 2f32.sqrt() {
+            1.0
+        } else if residual < 10f32.sqrt() {
+            2.0
+        } else if residual < 50f32.sqrt() {
+            5.0
+        } else {
+            10.0
+        };
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f32 * step).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_ignores_non_finite() {
+        assert_eq!(calc_min_max(&[1.0, f32::INFINITY, 3.0]), [1.0, 3.0]);
+        assert_eq!(
+            calc_min_max(&[f32::NAN, 2.0, f32::NEG_INFINITY]),
+            [2.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn min_max_falls_back_without_finite_values() {
+        assert_eq!(calc_min_max(&[f32::NAN, f32::INFINITY]), [0.0, 1.0]);
+        assert_eq!(calc_min_max(&[]), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn stats_skip_non_finite_values() {
+        let stats = calc_stats(vec![1.0, f32::NAN, -3.0, f32::INFINITY, 2.0]).unwrap();
+        assert_eq!((stats.min, stats.max, stats.mean), (-3.0, 2.0, 0.0));
+        assert!((stats.rms - (14.0f32 / 3.0).sqrt()).abs() < 1e-6);
+        assert!(calc_stats(vec![f32::NAN, f32::NEG_INFINITY]).is_none());
+    }
+
+    #[test]
+    fn normalize_equal_limits_is_finite() {
+        let (x, y) = normalize_point((0.5, 2.0), [3.0, 3.0], [0.0, 0.0], 0.8);
+        assert!(x.is_finite() && y.is_finite());
+        assert_eq!(x, 0.0);
+        assert_eq!(normalize_value(0.0, [3.0, 3.0], 0.8), -0.8);
+    }
+
+    #[test]
+    fn normalize_inverted_limits_flip_the_axis() {
+        assert_eq!(normalize_value(10.0, [10.0, 0.0], 1.0), -1.0);
+        assert_eq!(normalize_value(0.0, [10.0, 0.0], 1.0), 1.0);
+        assert_eq!(normalize_value(2.5, [10.0, 0.0], 1.0), 0.5);
+    }
+
+    #[test]
+    fn normalize_out_of_range_is_not_clamped() {
+        let (x, y) = normalize_point((-5.0, 20.0), [0.0, 10.0], [0.0, 10.0], 1.0);
+        assert_eq!((x, y), (-2.0, 3.0));
+        assert_eq!(denormalize_value(x, [0.0, 10.0], 1.0), -5.0);
+    }
+
+    /// Checks the ticks are increasing, evenly stepped and within the range.
+    fn assert_nice(ticks: &[f32], min: f32, max: f32) {
+        assert!(ticks.len() >= 2, "too few ticks: {:?}", ticks);
+        let step = ticks[1] - ticks[0];
+        assert!(step > 0.0);
+        for pair in ticks.windows(2) {
+            assert!(((pair[1] - pair[0]) - step).abs() <= step * 1e-3);
+        }
+        assert!(ticks[0] >= min && ticks[ticks.len() - 1] <= max);
+    }
+
+    #[test]
+    fn nice_ticks_negative_range() {
+        let ticks = nice_ticks(-7.3, -0.2, 5);
+        assert_nice(&ticks, -7.3, -0.2);
+        assert_eq!(ticks, vec![-6.0, -4.0, -2.0]);
+        assert_eq!(nice_ticks(-0.2, -7.3, 5), ticks);
+    }
+
+    #[test]
+    fn nice_ticks_tiny_span() {
+        let ticks = nice_ticks(0.0, 1e-6, 5);
+        assert_nice(&ticks, 0.0, 1e-6);
+        assert!((ticks[1] - ticks[0] - 2e-7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn nice_ticks_huge_span() {
+        let ticks = nice_ticks(0.0, 1e9, 5);
+        assert_nice(&ticks, 0.0, 1e9);
+        assert_eq!(ticks[1] - ticks[0], 2e8);
+    }
+
+    #[test]
+    fn nice_ticks_degenerate_inputs() {
+        assert_eq!(nice_ticks(3.0, 3.0, 5), vec![3.0]);
+        assert_eq!(nice_ticks(0.0, 10.0, 0), vec![0.0]);
+    }
+}
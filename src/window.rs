@@ -1,103 +1,255 @@
-use crate::figure::{FigureConfig, PlotType};
-use glium::glutin::dpi::LogicalSize;
+use crate::colormap::Color;
+use crate::figure::{self, Corner, FigureConfig, Layer, MarkerShape, PathStyle, PlotType};
+use crate::utils;
+use glium::glutin::dpi::{LogicalPosition, LogicalSize, Size};
+use glium::glutin::NotCurrent;
 use glium::uniform;
 use glium::{self, implement_vertex, Surface};
 use glium_text_rusttype as glium_text;
 use itertools_num::linspace;
-use lyon::math::{point, Point};
-use lyon::tessellation::basic_shapes::{fill_circle, fill_polyline, stroke_polyline, stroke_quad};
+use lyon::math::{point, rect, Point};
+use lyon::tessellation::basic_shapes::{
+    fill_circle, fill_polyline, fill_rectangle, stroke_polyline, stroke_quad,
+};
 use lyon::tessellation::geometry_builder::{BuffersBuilder, VertexBuffers, VertexConstructor};
 use lyon::tessellation::*;
 use lyon::tessellation::{FillOptions, StrokeOptions};
+use std::time::{Duration, Instant};
+
+/// The size in pixels the font is rasterized at unless set otherwise.
+pub(crate) static DEFAULT_FONT_SIZE: u32 = 70;
+
+/// The color the plot is cleared to before anything is drawn.
+static BACKGROUND: [u8; 3] = [169, 169, 169];
 
 pub static VERTEX_SHADER: &str = r#"
     #version 140
     in vec3 position;
     in vec3 rgb;
+    in float alpha;
     out vec3 rgb_frag;
+    out float alpha_frag;
     uniform mat4 projection;
     void main() {
         gl_Position = projection * vec4(position, 1.0);
         rgb_frag = rgb;
+        alpha_frag = alpha;
     }
 "#;
 
 pub static FRAGMENT_SHADER: &str = r#"
     #version 140
     in vec3 rgb_frag;
+    in float alpha_frag;
     out vec4 color;
     void main() {
-        color = vec4(rgb_frag, 1.0);
+        color = vec4(rgb_frag, alpha_frag);
     }
 "#;
 
+/// The vertex format used for everything drawn by the window. Positions are
+/// in plot coordinates, with the depth used to keep the data above the grid.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Vertex {
     position: [f32; 3],
     rgb: [f32; 3],
+    alpha: f32,
 }
 
-implement_vertex!(Vertex, position, rgb);
+implement_vertex!(Vertex, position, rgb, alpha);
 
 impl Vertex {
-    pub fn new(x: f32, y: f32, rgb: [u8; 3]) -> Self {
+    pub fn new(x: f32, y: f32, color: impl Into<Color>) -> Self {
+        Self::with_depth(x, y, color.into(), ZDepth::Far)
+    }
+
+    /// Returns the position of the vertex on the plot area. The plot spans
+    /// from `-plot_extent` to `plot_extent` along both axes.
+    pub fn position(&self) -> [f32; 2] {
+        [self.position[0], self.position[1]]
+    }
+
+    /// Returns the color of the vertex, with each channel from 0.0 to 1.0.
+    pub fn rgb(&self) -> [f32; 3] {
+        self.rgb
+    }
+
+    /// Returns the opacity of the vertex, from 0.0 for fully transparent to
+    /// 1.0 for opaque.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    fn with_depth(x: f32, y: f32, color: Color, depth: ZDepth) -> Self {
+        let Color { rgb, alpha } = color;
         let rgb: [f32; 3] = [
             f32::from(rgb[0]) / 255.0,
             f32::from(rgb[1]) / 255.0,
             f32::from(rgb[2]) / 255.0,
         ];
+        let z = match depth {
+            ZDepth::Far => 0.0,
+            ZDepth::Near => 1.0,
+        };
         Vertex {
-            position: [x, y, 0.0],
+            position: [x, y, z],
             rgb,
+            alpha: f32::from(alpha) / 255.0,
         }
     }
 }
 
+#[derive(Copy, Clone)]
 enum ZDepth {
     Near,
     Far,
 }
 
-struct VertexCtor([u8; 3], ZDepth);
+struct VertexCtor(Color, ZDepth);
 impl VertexConstructor<lyon::tessellation::StrokeVertex, Vertex> for VertexCtor {
     fn new_vertex(&mut self, vertex: lyon::tessellation::StrokeVertex) -> Vertex {
-        let rgb: [f32; 3] = [
-            f32::from(self.0[0]) / 255.0,
-            f32::from(self.0[1]) / 255.0,
-            f32::from(self.0[2]) / 255.0,
-        ];
-        let pos = vertex.position.to_array();
-        let position = match self.1 {
-            ZDepth::Far => [pos[0], pos[1], 0.0],
-            ZDepth::Near => [pos[0], pos[1], 1.0],
-        };
-        Vertex { position, rgb }
+        Vertex::with_depth(vertex.position.x, vertex.position.y, self.0, self.1)
     }
 }
 
 impl VertexConstructor<lyon::tessellation::FillVertex, Vertex> for VertexCtor {
     fn new_vertex(&mut self, vertex: lyon::tessellation::FillVertex) -> Vertex {
-        let rgb: [f32; 3] = [
-            f32::from(self.0[0]) / 255.0,
-            f32::from(self.0[1]) / 255.0,
-            f32::from(self.0[2]) / 255.0,
-        ];
-        let pos = vertex.position.to_array();
-        let position = match self.1 {
-            ZDepth::Far => [pos[0], pos[1], 0.0],
-            ZDepth::Near => [pos[0], pos[1], 1.0],
-        };
-        Vertex { position, rgb }
+        Vertex::with_depth(vertex.position.x, vertex.position.y, self.0, self.1)
+    }
+}
+
+/// Everything the cached grid and labels depend on that can change between
+/// frames. The cache is redrawn whenever any of these change.
+#[derive(Clone, PartialEq)]
+struct GridKey {
+    dimensions: (u32, u32),
+    xlim: Option<[f32; 2]>,
+    ylim: Option<[f32; 2]>,
+    xlabel: Option<String>,
+    ylabel: Option<String>,
+    categories: Option<Vec<String>>,
+    y_ticks: Option<Vec<f32>>,
+    grid_spacing: Option<[f32; 2]>,
+}
+
+/// Details of a frame passed to the callback set with `Figure::on_frame`.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameInfo {
+    /// The number of frames presented before this one.
+    pub index: u64,
+
+    /// When the frame was presented.
+    pub timestamp: Instant,
+
+    /// The frame rate going by the time since the previous frame, or zero
+    /// for the first frame.
+    pub fps: f32,
+
+    /// How long the frame took to tessellate and draw.
+    pub frame_time: Duration,
+
+    /// The number of data points drawn in the frame.
+    pub vertex_count: usize,
+}
+
+/// A callback run after each frame is presented.
+type FrameCallback<'a> = Box<dyn FnMut(&FrameInfo) + 'a>;
+
+/// A line drawn in its own color alongside the main data, such as a
+/// reference trace.
+pub struct Overlay {
+    pub vertices: Vec<Vertex>,
+    pub color: Color,
+}
+
+impl Clone for Overlay {
+    fn clone(&self) -> Self {
+        Overlay {
+            vertices: self.vertices.clone(),
+            color: self.color,
+        }
+    }
+
+    /// Reuses the allocation of the vertices, so overlays kept from frame to
+    /// frame aren't allocated afresh.
+    fn clone_from(&mut self, source: &Self) {
+        self.vertices.clone_from(&source.vertices);
+        self.color = source.color;
     }
 }
 
 pub struct Window<'a> {
-    pub events_loop: glium::glutin::event_loop::EventLoop<()>,
+    /// The event loop of the window, if the window was created here rather
+    /// than handed over by the caller.
+    pub events_loop: Option<glium::glutin::event_loop::EventLoop<()>>,
     display: glium::Display,
     program: glium::Program,
     draw_parameters: glium::DrawParameters<'a>,
     text_system: glium_text::TextSystem,
     font: glium_text::FontTexture,
+
+    /// The size in pixels the font is rasterized at, and the glyphs it has
+    /// beyond the first 255 characters.
+    font_size: u32,
+    extra_glyphs: Vec<char>,
+
+    /// The grid and labels from a previous frame, kept when `cache_grid` is
+    /// set along with what they were drawn for.
+    grid_cache: Option<(GridKey, glium::texture::Texture2d)>,
+
+    /// The data submitted since the last frame, waiting to be presented
+    /// together.
+    frame: Option<VertexBuffers<Vertex, u32>>,
+
+    /// A short message drawn above the top-right corner of the plot.
+    status: Option<String>,
+
+    /// Lines of statistics drawn in a corner of the plot.
+    stats: Option<Vec<String>>,
+
+    /// Opposite corners of a selection rectangle drawn over the plot, in
+    /// plot coordinates.
+    selection: Option<[[f32; 2]; 2]>,
+
+    /// A function of x drawn as a curve over the data each frame.
+    reference_fn: Option<Box<dyn Fn(f32) -> f32 + 'a>>,
+
+    /// Events marked along the bottom of the plot, as x positions in data
+    /// coordinates with optional labels, in order of position.
+    events: Vec<(f32, Option<String>)>,
+
+    /// Custom paths drawn over everything else, in data coordinates, with
+    /// their colors and how they're drawn.
+    paths: Vec<(lyon::path::Path, [u8; 3], PathStyle)>,
+
+    /// How long the most recent frame took to tessellate and draw.
+    pub last_frame_time: Duration,
+
+    /// Called with details of each frame once it's presented.
+    on_frame: Option<FrameCallback<'a>>,
+
+    /// The number of frames presented so far.
+    frame_index: u64,
+
+    /// When the previous frame was presented.
+    last_present: Option<Instant>,
+
+    /// The number of data points submitted to the frame waiting to be
+    /// presented.
+    frame_points: usize,
+
+    /// How long tessellating the data submitted to the frame took so far.
+    submit_time: Duration,
+
+    /// The vertices and overlays of the most recent draw outside of a
+    /// composited frame, drawn again by `redraw_with`. Only kept when
+    /// `keep_last_draw` is set.
+    last_draw: Option<(Vec<Vertex>, Vec<Overlay>)>,
+
+    /// The mesh of the most recently presented frame, along with any points
+    /// drawn directly by `fast_render`, kept for `redraw` when
+    /// `keep_last_draw` is set.
+    last_frame: Option<(VertexBuffers<Vertex, u32>, Vec<Point>)>,
 }
 
 impl<'a> Default for Window<'a> {
@@ -109,22 +261,27 @@ impl<'a> Default for Window<'a> {
 impl<'a> Window<'a> {
     pub fn new() -> Self {
         let events_loop = glium::glutin::event_loop::EventLoop::new();
-        let context = glium::glutin::ContextBuilder::new()
-            .with_vsync(true)
-            .with_double_buffer(Some(true))
-            .with_depth_buffer(24)
-            .with_multisampling(2);
-        let window = glium::glutin::window::WindowBuilder::new()
-            .with_inner_size(LogicalSize {
-                width: 800.0,
-                height: 800.0,
-            })
-            .with_decorations(true)
-            .with_title("Plot");
-
+        let (window, context) = Self::builders(LogicalSize {
+            width: 800.0,
+            height: 800.0,
+        });
         let display = glium::Display::new(window, context, &events_loop).unwrap();
-        let program =
-            glium::Program::from_source(&display, VERTEX_SHADER, FRAGMENT_SHADER, None).unwrap();
+        Self::from_parts(display, Some(events_loop))
+    }
+
+    /// Draws into an existing display instead of creating a window. The
+    /// caller keeps ownership of the event loop, so handles the window's
+    /// events. The display needs a depth buffer for the data to be layered
+    /// above the grid.
+    pub fn with_display(display: glium::Display) -> Self {
+        Self::from_parts(display, None)
+    }
+
+    fn from_parts(
+        display: glium::Display,
+        events_loop: Option<glium::glutin::event_loop::EventLoop<()>>,
+    ) -> Self {
+        let (program, text_system, font) = Self::resources(&display, DEFAULT_FONT_SIZE, &[]);
 
         let draw_parameters = glium::DrawParameters {
             depth: glium::Depth {
@@ -132,18 +289,10 @@ impl<'a> Window<'a> {
                 test: glium::DepthTest::IfLess,
                 ..Default::default()
             },
+            blend: glium::Blend::alpha_blending(),
             ..Default::default()
         };
 
-        let text_system = glium_text::TextSystem::new(&display);
-        let font = glium_text::FontTexture::new(
-            &display,
-            ttf_noto_sans::REGULAR,
-            70,
-            glium_text::FontTexture::ascii_character_list(),
-        )
-        .unwrap();
-
         Self {
             events_loop,
             display,
@@ -151,217 +300,2269 @@ impl<'a> Window<'a> {
             draw_parameters,
             text_system,
             font,
+            font_size: DEFAULT_FONT_SIZE,
+            extra_glyphs: vec![],
+            grid_cache: None,
+            frame: None,
+            status: None,
+            selection: None,
+            reference_fn: None,
+            events: vec![],
+            paths: vec![],
+            stats: None,
+            last_frame_time: Duration::default(),
+            on_frame: None,
+            frame_index: 0,
+            last_present: None,
+            frame_points: 0,
+            submit_time: Duration::default(),
+            last_draw: None,
+            last_frame: None,
         }
     }
 
-    pub fn draw(&mut self, vertices: &[Vertex], config: &FigureConfig) {
-        let mut target = self.display.draw();
-        let color = (169.0 / 255.0, 169.0 / 255.0, 169.0 / 255.0, 1.0);
-        target.clear_color_and_depth(color, 1.0);
-        let mut mesh: VertexBuffers<Vertex, u32> = VertexBuffers::new();
-        self.draw_text(&mut target, config);
-        self.draw_grid(&mut mesh);
+    /// Returns the builders for the window and its GL context.
+    fn builders(
+        size: impl Into<Size>,
+    ) -> (
+        glium::glutin::window::WindowBuilder,
+        glium::glutin::ContextBuilder<'static, NotCurrent>,
+    ) {
+        let context = glium::glutin::ContextBuilder::new()
+            .with_vsync(true)
+            .with_double_buffer(Some(true))
+            .with_depth_buffer(24)
+            .with_multisampling(2);
+        let window = glium::glutin::window::WindowBuilder::new()
+            .with_inner_size(size)
+            .with_decorations(true)
+            .with_title("Plot");
+        (window, context)
+    }
 
-        let points: Vec<Point> = vertices
-            .iter()
-            .map(|x| point(x.position[0], x.position[1]))
-            .collect();
+    /// Creates the shader program and text rendering for the display, with
+    /// the font rasterized at the given size in pixels.
+    fn resources(
+        display: &glium::Display,
+        font_size: u32,
+        extra_glyphs: &[char],
+    ) -> (
+        glium::Program,
+        glium_text::TextSystem,
+        glium_text::FontTexture,
+    ) {
+        let program =
+            glium::Program::from_source(display, VERTEX_SHADER, FRAGMENT_SHADER, None).unwrap();
+        let text_system = glium_text::TextSystem::new(display);
+        let font = Self::font(display, font_size, extra_glyphs);
+        (program, text_system, font)
+    }
 
-        match config.plot_type {
-            PlotType::Line => {
-                stroke_polyline(
-                    points.iter().cloned(),
-                    false,
-                    &StrokeOptions::tolerance(0.01).with_line_width(0.002),
-                    &mut BuffersBuilder::new(&mut mesh, VertexCtor(config.color, ZDepth::Near)),
-                )
-                .expect("Could not draw line plot");
-            }
-            PlotType::Dot => {
-                for point in points {
-                    fill_circle(
-                        point,
-                        0.01,
-                        &FillOptions::tolerance(0.01),
-                        &mut BuffersBuilder::new(&mut mesh, VertexCtor(config.color, ZDepth::Near)),
-                    )
-                    .expect("Could not draw dot plot");
-                }
-            }
+    /// Rasterizes the font at the given size in pixels, with the first 255
+    /// characters of Unicode along with any extra glyphs.
+    fn font(
+        display: &glium::Display,
+        font_size: u32,
+        extra_glyphs: &[char],
+    ) -> glium_text::FontTexture {
+        let mut characters = glium_text::FontTexture::ascii_character_list();
+        characters.extend(extra_glyphs.iter().filter(|c| u32::from(**c) >= 255));
+        glium_text::FontTexture::new(display, ttf_noto_sans::REGULAR, font_size, characters)
+            .expect("Could not rasterize the font")
+    }
+
+    /// Rasterizes the font used for text again at the given size in pixels,
+    /// with any extra glyphs beyond the first 255 characters of Unicode.
+    /// Larger sizes keep text crisp when it's drawn large or on hi-DPI
+    /// displays, at the cost of memory and startup time. Text is laid out the
+    /// same whatever the size.
+    pub fn set_font(&mut self, font_size: u32, extra_glyphs: &[char]) {
+        self.font = Self::font(&self.display, font_size.max(1), extra_glyphs);
+        self.font_size = font_size.max(1);
+        self.extra_glyphs = extra_glyphs.to_vec();
+        self.grid_cache = None;
+    }
+
+    /// Presents a finished frame. If the GL context was lost, such as from a
+    /// driver reset or a switch between GPUs, the window and everything drawn
+    /// with it are recreated so the next frame can be drawn as normal.
+    fn finish(&mut self, target: glium::Frame) {
+        match target.finish() {
+            Err(glium::SwapBuffersError::ContextLost) => self.recreate(),
+            result => result.expect("Could not finish the frame"),
         }
+    }
 
-        let (w, h) = self.display.get_framebuffer_dimensions();
-        let aspect = w as f32 / h as f32;
-        let ortho_mat = cgmath::ortho(-aspect, aspect, -1.0, 1.0, -1.0, 1.0);
-        let ortho: &[[f32; 4]; 4] = ortho_mat.as_ref();
-        let uniforms = uniform! {
-            projection: *ortho,
+    /// Keeps the mesh of a presented frame, along with any points drawn
+    /// directly, for `redraw` if `keep_last_draw` is set. The mesh is moved
+    /// rather than copied.
+    fn keep_frame(
+        &mut self,
+        mesh: VertexBuffers<Vertex, u32>,
+        points: Vec<Point>,
+        config: &FigureConfig,
+    ) {
+        self.last_frame = if config.keep_last_draw {
+            Some((mesh, points))
+        } else {
+            None
         };
+    }
 
-        let vertex_buffer = glium::VertexBuffer::new(&self.display, &mesh.vertices)
-            .expect("Could not create vertex buffer");
-        let indices = glium::IndexBuffer::new(
-            &self.display,
-            glium::index::PrimitiveType::TrianglesList,
-            &mesh.indices,
-        )
-        .expect("Could not create index buffer");
+    /// Presents the mesh of the most recent frame again over a freshly drawn
+    /// background, without tessellating anything, such as when the window is
+    /// uncovered or regains focus and the window system has thrown its
+    /// contents away. Does nothing unless the frame was presented with
+    /// `keep_last_draw` set, and isn't counted as a new frame by the frame
+    /// callback.
+    pub fn redraw(&mut self, config: &FigureConfig) {
+        let (mesh, points) = match self.last_frame.take() {
+            Some(last_frame) => last_frame,
+            None => return,
+        };
+        let mut target = self.display.draw();
+        self.draw_frame_background(&mut target, config);
+        self.draw_mesh(&mut target, &mesh, config);
+        if !points.is_empty() {
+            self.draw_primitives(&mut target, &points, config);
+        }
+        self.last_frame = Some((mesh, points));
+        self.finish(target);
+    }
 
-        target
-            .draw(
-                &vertex_buffer,
-                &indices,
-                &self.program,
-                &uniforms,
-                &self.draw_parameters,
-            )
-            .expect("Could not draw the frame");
+    /// Recreates the window at its current size along with the GL objects
+    /// that were lost with the context. A display handed over by the caller
+    /// is theirs to rebuild, so only the GL objects are recreated.
+    fn recreate(&mut self) {
+        if let Some(events_loop) = &self.events_loop {
+            let size = self.display.gl_window().window().inner_size();
+            let (window, context) = Self::builders(size);
+            self.display
+                .rebuild(window, context, events_loop)
+                .expect("Could not recreate the window after losing the GL context");
+        }
+        let (program, text_system, font) =
+            Self::resources(&self.display, self.font_size, &self.extra_glyphs);
+        self.program = program;
+        self.text_system = text_system;
+        self.font = font;
+        self.grid_cache = None;
+    }
 
-        target.finish().expect("Could not finish the frame");
+    /// Moves the window to the given position on the screen.
+    pub fn set_position(&self, x: f64, y: f64) {
+        self.display
+            .gl_window()
+            .window()
+            .set_outer_position(LogicalPosition::new(x, y));
     }
 
-    fn draw_text<S>(&mut self, target: &mut S, config: &FigureConfig)
-    where
-        S: glium::Surface,
-    {
-        let (w, h) = self.display.get_framebuffer_dimensions();
-        let aspect = w as f32 / h as f32;
-        let ortho_mat = cgmath::ortho(-aspect, aspect, -1.0, 1.0, -1.0, 1.0);
-        if let Some(text) = config.xlabel {
-            let label = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
-            let text_width = label.get_width() * 0.1;
-            #[rustfmt::skip]
-            let matrix = ortho_mat * cgmath::Matrix4::new(
-                0.1, 0.0, 0.0, 0.0,
-                0.0, 0.1, 0.0, 0.0,
-                0.0, 0.0, 0.1, 0.0,
-                -text_width / 2.0, -0.90, 0.0, 1.0,
-            );
-            glium_text::draw(
-                &label,
-                &self.text_system,
-                target,
-                matrix,
-                (0.0, 0.0, 0.0, 1.0),
-            )
-            .expect("Could not draw x label");
+    /// Draws the vertices and overlays. Between `begin_frame` and `present`
+    /// they're only submitted to the frame; otherwise they're submitted and
+    /// presented straight away. Line and Dot plots drawn with `fast_render`
+    /// outside of a frame skip the mesh and are drawn directly.
+    pub fn draw(&mut self, vertices: &[Vertex], overlays: &[Overlay], config: &FigureConfig) {
+        if self.frame.is_none() {
+            if config.keep_last_draw {
+                let (last_vertices, last_overlays) =
+                    self.last_draw.get_or_insert_with(Default::default);
+                last_vertices.clear();
+                last_vertices.extend_from_slice(vertices);
+                overlays.clone_into(last_overlays);
+            } else {
+                self.last_draw = None;
+            }
         }
-        if let Some(text) = config.ylabel {
-            let label = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
-            let text_width = label.get_width() * 0.1;
-            #[rustfmt::skip]
-            let matrix = ortho_mat * cgmath::Matrix4::new(
-                0.1, 0.0, 0.0, 0.0,
-                0.0, 0.1, 0.0, 0.0,
-                0.0, 1.0, 0.1, 0.0,
-                -0.90, -text_width / 2.0, 0.0, 1.0,
-            ) * cgmath::Matrix4::from_angle_z(cgmath::Deg(90.0));
-            glium_text::draw(
-                &label,
-                &self.text_system,
-                target,
-                matrix,
-                (0.0, 0.0, 0.0, 1.0),
+        let fast_render =
+            config.fast_render && matches!(config.plot_type, PlotType::Line | PlotType::Dot);
+        if self.frame.is_some() || !fast_render {
+            let in_frame = self.frame.is_some();
+            self.submit(vertices, overlays, config);
+            if !in_frame {
+                self.present(config);
+            }
+            return;
+        }
+        let start = Instant::now();
+        let mut target = self.display.draw();
+        let (mesh, points) = self.render(&mut target, vertices, overlays, config);
+        self.finish(target);
+        self.keep_frame(mesh, points, config);
+        self.last_frame_time = start.elapsed();
+        self.frame_presented(vertices.len());
+    }
+
+    /// Draws filled cells, such as those of a heatmap, each given by opposite
+    /// corners in plot coordinates and a color. As with `draw`, the cells are
+    /// only submitted between `begin_frame` and `present`, and presented
+    /// straight away otherwise.
+    pub fn draw_cells(&mut self, cells: &[([f32; 4], [u8; 3])], config: &FigureConfig) {
+        let start = Instant::now();
+        let in_frame = self.frame.is_some();
+        if !in_frame {
+            // There are no vertices to draw again with `redraw_with`.
+            self.last_draw = None;
+        }
+        self.frame_points += cells.len();
+        let mesh = self.frame.get_or_insert_with(VertexBuffers::new);
+        let options = FillOptions::tolerance(Self::tolerance(config));
+        for ([x0, y0, x1, y1], color) in cells {
+            fill_rectangle(
+                &rect(x0.min(*x1), y0.min(*y1), (x1 - x0).abs(), (y1 - y0).abs()),
+                &options,
+                &mut BuffersBuilder::new(mesh, VertexCtor((*color).into(), ZDepth::Near)),
             )
-            .expect("Could not draw y label");
+            .expect("Could not draw cell");
         }
-        if let Some([xmin, xmax]) = config.xlim {
-            for (coord, tick) in linspace(-0.75, 0.75, 6).zip(linspace(xmin, xmax, 6)) {
-                let tick_str = glium_text::TextDisplay::new(
-                    &self.text_system,
-                    &self.font,
-                    &format!("{:.02}", tick),
-                );
-                let text_width = tick_str.get_width() * 0.05;
-                #[rustfmt::skip]
-                let matrix = ortho_mat * cgmath::Matrix4::new(
-                    0.05, 0.0, 0.0, 0.0,
-                    0.0, 0.05, 0.0, 0.0,
-                    0.0, 0.0, 0.05, 0.0,
-                    coord - text_width / 2.0, -0.80, 0.0, 1.0,
-                );
-                glium_text::draw(
-                    &tick_str,
-                    &self.text_system,
-                    target,
-                    matrix,
-                    (0.0, 0.0, 0.0, 1.0),
-                )
-                .expect("Could not draw x axis values");
-            }
+        self.submit_time += start.elapsed();
+        if !in_frame {
+            self.present(config);
         }
-        if let Some([ymin, ymax]) = config.ylim {
-            for (coord, tick) in linspace(-0.75, 0.75, 5).zip(linspace(ymin, ymax, 5)) {
-                let tick_str = glium_text::TextDisplay::new(
-                    &self.text_system,
-                    &self.font,
-                    &format!("{:.02}", tick),
-                );
-                let text_height = tick_str.get_height() * 0.05;
-                #[rustfmt::skip]
-                let matrix = ortho_mat * cgmath::Matrix4::new(
-                    0.05, 0.0, 0.0, 0.0,
-                    0.0, 0.05, 0.0, 0.0,
-                    0.0, 0.0, 0.05, 0.0,
-                    -0.85, coord - text_height / 2.0, 0.0, 1.0,
-                );
-                glium_text::draw(
-                    &tick_str,
-                    &self.text_system,
-                    target,
-                    matrix,
-                    (0.0, 0.0, 0.0, 1.0),
-                )
-                .expect("Could not draw y axis labels");
-            }
+    }
+
+    /// Draws the vertices and overlays from the most recent draw again with
+    /// the given configuration, so changes to how the plot looks show up
+    /// without new data. If the last frame was composited with `begin_frame`
+    /// there's no single draw to repeat, so its mesh is presented again as
+    /// with `redraw`. Does nothing if nothing has been drawn yet.
+    pub fn redraw_with(&mut self, config: &FigureConfig) {
+        match self.last_draw.take() {
+            Some((vertices, overlays)) => self.draw(&vertices, &overlays, config),
+            None => self.redraw(config),
         }
     }
 
-    fn draw_grid(&mut self, mesh: &mut VertexBuffers<Vertex, u32>) {
-        let mut tessellator = FillTessellator::new();
+    /// Tessellates the vertices and overlays into the frame waiting to be
+    /// presented, starting a new frame if there isn't one, without drawing
+    /// anything to the window yet.
+    pub fn submit(&mut self, vertices: &[Vertex], overlays: &[Overlay], config: &FigureConfig) {
+        let start = Instant::now();
+        self.frame_points += vertices.len();
+        let reference = self.reference_overlay(self.framebuffer_size(), config);
+        let paths = self.placed_paths(config);
+        let mesh = self.frame.get_or_insert_with(VertexBuffers::new);
+        let points = Self::points(vertices);
+        let reference = reference.as_ref();
+        Self::draw_layers(mesh, &points, overlays, reference, &paths, config, true);
+        self.submit_time += start.elapsed();
+    }
 
-        for tick in linspace(-0.75, 0.75, 6) {
-            fill_polyline(
-                [
-                    point(tick - 0.001, 0.75),
-                    point(tick - 0.001, -0.75),
-                    point(tick + 0.001, -0.75),
-                    point(tick + 0.001, 0.75),
-                ]
-                .iter()
-                .cloned(),
-                &mut tessellator,
-                &FillOptions::tolerance(0.01),
-                &mut BuffersBuilder::new(mesh, VertexCtor([0x5d, 0x5d, 0x5d], ZDepth::Far)),
-            )
-            .expect("Could not draw grid");
+    /// Sets a callback to run after each frame is presented, or clears it.
+    pub fn set_on_frame(&mut self, on_frame: Option<FrameCallback<'a>>) {
+        self.on_frame = on_frame;
+    }
+
+    /// Counts a presented frame and passes its details to the frame
+    /// callback, if there is one.
+    fn frame_presented(&mut self, vertex_count: usize) {
+        let now = Instant::now();
+        let fps = match self.last_present {
+            Some(last) => 1.0 / now.duration_since(last).as_secs_f32().max(f32::EPSILON),
+            None => 0.0,
+        };
+        let info = FrameInfo {
+            index: self.frame_index,
+            timestamp: now,
+            fps,
+            frame_time: self.last_frame_time,
+            vertex_count,
+        };
+        self.frame_index += 1;
+        self.last_present = Some(now);
+        if let Some(on_frame) = self.on_frame.as_mut() {
+            on_frame(&info);
         }
+    }
 
-        for tick in linspace(-0.75, 0.75, 5) {
-            fill_polyline(
-                [
-                    point(0.75, tick - 0.001),
-                    point(-0.75, tick - 0.001),
-                    point(-0.75, tick + 0.001),
-                    point(0.75, tick + 0.001),
-                ]
+    /// Returns the vertices and overlays of the most recent draw outside of
+    /// a composited frame, or None if the last frame was composited or drawn
+    /// from cells, or `keep_last_draw` isn't set.
+    pub fn last_draw(&self) -> Option<(&[Vertex], &[Overlay])> {
+        self.last_draw
+            .as_ref()
+            .map(|(vertices, overlays)| (vertices.as_slice(), overlays.as_slice()))
+    }
+
+    /// Returns the size of the window's framebuffer in pixels.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        self.display.get_framebuffer_dimensions()
+    }
+
+    /// Returns how far the edges of the window are from the center in plot
+    /// coordinates, horizontally and vertically. Dividing plot coordinates by
+    /// these gives normalized device coordinates.
+    pub fn half_extents(&self, config: &FigureConfig) -> [f32; 2] {
+        Self::half_extents_of(self.display.get_framebuffer_dimensions(), config)
+    }
+
+    /// Returns how far the edges of a target of the given size are from the
+    /// center in plot coordinates.
+    fn half_extents_of((w, h): (u32, u32), config: &FigureConfig) -> [f32; 2] {
+        let aspect = w as f32 / h as f32;
+        // Matches the scaling applied by `projection`.
+        if config.square_plot && aspect < 1.0 {
+            [1.0, 1.0 / aspect]
+        } else {
+            [aspect, 1.0]
+        }
+    }
+
+    /// Lays out the grid, ticks, labels and data as they'd be drawn on a
+    /// target of the given size and returns them as an SVG document. Lines
+    /// become polylines, markers become circles, rectangles or polygons and
+    /// text stays text, so the figure can be scaled without losing quality.
+    pub fn render_svg(
+        dimensions: (u32, u32),
+        vertices: &[Vertex],
+        overlays: &[Overlay],
+        config: &FigureConfig,
+    ) -> String {
+        let (w, h) = dimensions;
+        let [half_w, half_h] = Self::half_extents_of(dimensions, config);
+        let scale = h as f32 / (2.0 * half_h);
+        let px = |x: f32, y: f32| {
+            (
+                (x / half_w + 1.0) * w as f32 / 2.0,
+                (1.0 - y / half_h) * h as f32 / 2.0,
+            )
+        };
+        let hex = |Color {
+                       rgb: [r, g, b],
+                       alpha,
+                   }: Color| match alpha {
+            255 => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            _ => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, alpha),
+        };
+        let polyline = |points: &[Point]| {
+            points
                 .iter()
-                .cloned(),
-                &mut tessellator,
-                &FillOptions::tolerance(0.01),
-                &mut BuffersBuilder::new(mesh, VertexCtor([0x5d, 0x5d, 0x5d], ZDepth::Far)),
+                .map(|p| {
+                    let (x, y) = px(p.x, p.y);
+                    format!("{:.2},{:.2}", x, y)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let text = |x: f32, y: f32, size: f32, anchor: &str, content: &str| {
+            let (x, y) = px(x, y);
+            let content = content
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            format!(
+                "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"{}\">{}</text>\n",
+                x,
+                y,
+                size * scale,
+                anchor,
+                content
             )
-            .expect("Could not draw grid");
+        };
+
+        let extent = config.layout.plot_extent;
+        let layout = config.layout;
+        let (x_target, y_target) = Self::tick_targets(dimensions, config);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = w,
+            h = h
+        );
+        svg += &format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+            w, h
+        );
+
+        // Gridlines and the plot border.
+        let grid_width = 0.002 * scale;
+        for tick in Self::x_grid_coords(x_target, config) {
+            svg += &format!(
+                "<polyline points=\"{}\" stroke=\"#5d5d5d\" stroke-width=\"{:.2}\" fill=\"none\"/>\n",
+                polyline(&[point(tick, extent), point(tick, -extent)]),
+                grid_width
+            );
+        }
+        for tick in Self::y_grid_coords(y_target, config) {
+            svg += &format!(
+                "<polyline points=\"{}\" stroke=\"#5d5d5d\" stroke-width=\"{:.2}\" fill=\"none\"/>\n",
+                polyline(&[point(-extent, tick), point(extent, tick)]),
+                grid_width
+            );
         }
+        svg += &format!(
+            "<polygon points=\"{}\" stroke=\"#000000\" stroke-width=\"{:.2}\" fill=\"none\"/>\n",
+            polyline(&[
+                point(-extent, -extent),
+                point(-extent, extent),
+                point(extent, extent),
+                point(extent, -extent),
+            ]),
+            0.001 * scale
+        );
 
-        stroke_quad(
-            point(-0.75, -0.75),
-            point(-0.75, 0.75),
-            point(0.75, 0.75),
-            point(0.75, -0.75),
-            &StrokeOptions::tolerance(0.01).with_line_width(0.001),
-            &mut BuffersBuilder::new(mesh, VertexCtor([0, 0, 0], ZDepth::Near)),
-        )
-        .unwrap();
+        // Labels and tick values.
+        if let Some(label) = &config.xlabel {
+            svg += &text(0.0, layout.xlabel_offset, 0.1, "middle", label);
+        }
+        if let Some(label) = &config.ylabel {
+            let (x, y) = px(layout.ylabel_offset, 0.0);
+            svg += &format!(
+                "<g transform=\"rotate(-90 {:.2} {:.2})\">{}</g>\n",
+                x,
+                y,
+                text(layout.ylabel_offset, 0.0, 0.1, "middle", label).trim_end()
+            );
+        }
+        if let Some(categories) = &config.categories {
+            let count = categories.len();
+            for (i, category) in categories.iter().enumerate() {
+                let coord = extent * (2.0 * (i as f32 + 0.5) / count as f32 - 1.0);
+                svg += &text(coord, layout.xtick_offset, 0.05, "middle", category);
+            }
+        } else if let Some(xlim) = config.xlim {
+            let ticks = Self::x_ticks(xlim, x_target, config);
+            let stepped = Self::stepped(x_target, Self::x_spacing(config));
+            for (coord, value) in Self::tick_labels(&ticks, stepped, config) {
+                svg += &text(coord, layout.xtick_offset, 0.05, "middle", &value);
+            }
+        }
+        if let Some(ylim) = config.ylim {
+            let ticks = Self::y_ticks(ylim, y_target, config);
+            let stepped = Self::stepped(y_target, Self::y_spacing(config));
+            for (coord, value) in Self::tick_labels(&ticks, stepped, config) {
+                svg += &text(layout.ytick_offset, coord, 0.05, "start", &value);
+            }
+        }
+
+        // Overlays beneath the data, each as a line in its own color.
+        let line_width = 0.002 * scale;
+        for overlay in overlays {
+            svg += &format!(
+                "<polyline points=\"{}\" stroke=\"{}\" stroke-width=\"{:.2}\" fill=\"none\"/>\n",
+                polyline(&Self::points(&overlay.vertices)),
+                hex(overlay.color),
+                line_width
+            );
+        }
+
+        // The data itself.
+        let points = Self::points(vertices);
+        let size = config.marker_size.unwrap_or(0.01);
+        let marker = |p: &Point, color: Color| match config.marker_shape {
+            MarkerShape::Circle => {
+                let (x, y) = px(p.x, p.y);
+                format!(
+                    "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\"/>\n",
+                    x,
+                    y,
+                    size * scale,
+                    hex(color)
+                )
+            }
+            MarkerShape::Square => {
+                let (x, y) = px(p.x - size, p.y + size);
+                format!(
+                    "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>\n",
+                    x,
+                    y,
+                    2.0 * size * scale,
+                    2.0 * size * scale,
+                    hex(color)
+                )
+            }
+            MarkerShape::Diamond => format!(
+                "<polygon points=\"{}\" fill=\"{}\"/>\n",
+                polyline(&[
+                    point(p.x, p.y + size),
+                    point(p.x + size, p.y),
+                    point(p.x, p.y - size),
+                    point(p.x - size, p.y),
+                ]),
+                hex(color)
+            ),
+        };
+        let line = |segment: &[Point], color: Color| {
+            format!(
+                "<polyline points=\"{}\" stroke=\"{}\" stroke-width=\"{:.2}\" fill=\"none\"/>\n",
+                polyline(segment),
+                hex(color),
+                line_width
+            )
+        };
+        let count = points.len();
+        let draw_line = matches!(config.plot_type, PlotType::Line | PlotType::LineDot);
+        let draw_markers = matches!(config.plot_type, PlotType::Dot | PlotType::LineDot);
+        if draw_line {
+            for (segment, color) in Self::segments(&points, config) {
+                svg += &line(&segment, color);
+            }
+        }
+        if draw_markers {
+            for (i, p) in points.iter().enumerate() {
+                svg += &marker(p, Self::point_color(p, i, count, config));
+            }
+        }
+        let baseline = Self::baseline(config);
+        match config.plot_type {
+            PlotType::Stem => {
+                for (i, p) in points.iter().enumerate() {
+                    let color = Self::point_color(p, i, count, config);
+                    svg += &line(&[point(p.x, baseline), *p], color);
+                    svg += &marker(p, color);
+                }
+            }
+            PlotType::Bar => {
+                let width = 1.6 * extent / count.max(1) as f32;
+                for (i, p) in points.iter().enumerate() {
+                    let (x, y) = px(p.x - width / 2.0, p.y.max(baseline));
+                    svg += &format!(
+                        "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>\n",
+                        x,
+                        y,
+                        width * scale,
+                        (p.y - baseline).abs() * scale,
+                        hex(Self::point_color(p, i, count, config))
+                    );
+                }
+            }
+            PlotType::Area => {
+                for (segment, color) in Self::segments(&points, config) {
+                    if let (Some(first), Some(last)) = (segment.first(), segment.last()) {
+                        let outline: Vec<Point> = std::iter::once(point(first.x, baseline))
+                            .chain(segment.iter().cloned())
+                            .chain(std::iter::once(point(last.x, baseline)))
+                            .collect();
+                        svg += &format!(
+                            "<polygon points=\"{}\" fill=\"{}\"/>\n",
+                            polyline(&outline),
+                            hex(color)
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    /// Returns the width of the plot area in pixels.
+    pub fn plot_width(&self, config: &FigureConfig) -> f32 {
+        Self::plot_size(self.display.get_framebuffer_dimensions(), config)
+    }
+
+    /// Returns the width of the plot area in pixels on a target of the given
+    /// size. The plot is square, so this is also its height.
+    fn plot_size((w, h): (u32, u32), config: &FigureConfig) -> f32 {
+        let aspect = w as f32 / h as f32;
+        // Matches the scaling applied by `projection`.
+        let pixels_per_unit = if config.square_plot && aspect < 1.0 {
+            w as f32 / 2.0
+        } else {
+            h as f32 / 2.0
+        };
+        2.0 * config.layout.plot_extent * pixels_per_unit
+    }
+
+    /// Sets a short message to draw above the top-right corner of the plot,
+    /// or clears it.
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.status = status;
+    }
+
+    /// Sets opposite corners, in plot coordinates, of a selection rectangle
+    /// to draw over the plot, or clears it.
+    pub fn set_selection(&mut self, selection: Option<[[f32; 2]; 2]>) {
+        self.selection = selection;
+    }
+
+    /// Adds events to mark along the bottom of the plot at the given x
+    /// positions, all with the same optional label.
+    pub fn add_events(&mut self, times: &[f32], label: Option<&str>) {
+        self.events.extend(
+            times
+                .iter()
+                .map(|time| (*time, label.map(|label| label.to_string()))),
+        );
+        self.events.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    /// Removes every event marked with `add_events`.
+    pub fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// Adds a path in data coordinates to draw over everything else.
+    pub fn add_path(&mut self, path: lyon::path::Path, color: [u8; 3], style: PathStyle) {
+        self.paths.push((path, color, style));
+    }
+
+    /// Removes every path added with `add_path`.
+    pub fn clear_paths(&mut self) {
+        self.paths.clear();
+    }
+
+    /// Maps the custom paths from data coordinates onto the plot area using
+    /// the current limits. Without both limits, there's nothing to map them
+    /// onto yet.
+    fn placed_paths(&self, config: &FigureConfig) -> Vec<(lyon::path::Path, [u8; 3], PathStyle)> {
+        let (xlim, ylim) = match (config.xlim, config.ylim) {
+            (Some(xlim), Some(ylim)) => (xlim, ylim),
+            _ => return vec![],
+        };
+        let extent = config.layout.plot_extent;
+        self.paths
+            .iter()
+            .map(|(path, color, style)| {
+                // The mapping is affine, so mapping the control points of the
+                // curves maps the curves themselves.
+                let mut path = path.clone();
+                for pt in path.mut_points() {
+                    let (x, y) = utils::normalize_point((pt.x, pt.y), xlim, ylim, extent);
+                    *pt = point(x, y);
+                }
+                (path, *color, *style)
+            })
+            .collect()
+    }
+
+    /// Sets a function of x to draw as a curve over the data, or clears it.
+    pub fn set_reference_fn(&mut self, reference_fn: Option<Box<dyn Fn(f32) -> f32 + 'a>>) {
+        self.reference_fn = reference_fn;
+    }
+
+    /// Samples the reference function once per pixel across the x limits on
+    /// a target of the given size, returning it as a line to draw.
+    fn reference_overlay(&self, dimensions: (u32, u32), config: &FigureConfig) -> Option<Overlay> {
+        let reference_fn = self.reference_fn.as_ref()?;
+        // Without both limits, there's nothing to map the curve onto yet.
+        let [min, max] = config.xlim.filter(|_| config.ylim.is_some())?;
+        let count = (Self::plot_size(dimensions, config).round() as usize).max(2);
+        let points: Vec<(f32, f32)> = linspace(min, max, count)
+            .map(|x| (x, reference_fn(x)))
+            .collect();
+        Some(Overlay {
+            vertices: figure::compute_vertices(config, &points),
+            color: config.reference_color.into(),
+        })
+    }
+
+    /// Sets the lines of statistics to draw in a corner of the plot, or
+    /// clears them.
+    pub fn set_stats(&mut self, stats: Option<Vec<String>>) {
+        self.stats = stats;
+    }
+
+    /// Clears the lines of statistics, returning them so they can be reused.
+    pub fn take_stats(&mut self) -> Option<Vec<String>> {
+        self.stats.take()
+    }
+
+    /// Starts collecting draws into a single frame, discarding anything
+    /// submitted but not yet presented. Until `present` is called, each draw
+    /// is submitted to the frame instead of being presented.
+    pub fn begin_frame(&mut self) {
+        self.frame = Some(VertexBuffers::new());
+        self.last_draw = None;
+        self.frame_points = 0;
+        self.submit_time = Duration::default();
+    }
+
+    /// Clears the window and presents everything submitted since the last
+    /// frame together, behind a single grid and set of labels drawn for the
+    /// given configuration. Does nothing if nothing has been submitted.
+    pub fn present(&mut self, config: &FigureConfig) {
+        let mesh = match self.frame.take() {
+            Some(mesh) => mesh,
+            None => return,
+        };
+        let start = Instant::now();
+        let mut target = self.display.draw();
+        self.draw_frame_background(&mut target, config);
+        self.draw_mesh(&mut target, &mesh, config);
+        self.finish(target);
+        self.keep_frame(mesh, vec![], config);
+        self.last_frame_time = self.submit_time + start.elapsed();
+        self.submit_time = Duration::default();
+        self.frame_presented(self.frame_points);
+        self.frame_points = 0;
+    }
+
+    /// Renders a frame offscreen at the given size, returning its pixels as
+    /// RGBA bytes with the top row first.
+    pub fn render_to_rgba(
+        &mut self,
+        width: u32,
+        height: u32,
+        vertices: &[Vertex],
+        overlays: &[Overlay],
+        config: &FigureConfig,
+    ) -> Vec<u8> {
+        let texture = glium::texture::Texture2d::empty_with_format(
+            &self.display,
+            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+            glium::texture::MipmapsOption::NoMipmap,
+            width,
+            height,
+        )
+        .expect("Could not create offscreen texture");
+        let depth = glium::framebuffer::DepthRenderBuffer::new(
+            &self.display,
+            glium::texture::DepthFormat::I24,
+            width,
+            height,
+        )
+        .expect("Could not create offscreen depth buffer");
+        let mut target = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(
+            &self.display,
+            &texture,
+            &depth,
+        )
+        .expect("Could not create offscreen framebuffer");
+        self.render(&mut target, vertices, overlays, config);
+
+        // GL reads the image bottom row first, so flip it to match the usual
+        // image layout.
+        let image: glium::texture::RawImage2d<u8> = texture.read();
+        let row_len = width as usize * 4;
+        image
+            .data
+            .chunks(row_len)
+            .rev()
+            .flat_map(|row| row.iter().cloned())
+            .collect()
+    }
+
+    /// Renders the plot, its labels and the data onto the target. Returns
+    /// the tessellated mesh along with the points drawn directly, if any.
+    fn render<S>(
+        &mut self,
+        target: &mut S,
+        vertices: &[Vertex],
+        overlays: &[Overlay],
+        config: &FigureConfig,
+    ) -> (VertexBuffers<Vertex, u32>, Vec<Point>)
+    where
+        S: glium::Surface,
+    {
+        self.draw_frame_background(target, config);
+
+        let mut mesh: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        let points = Self::points(vertices);
+        let fast_render =
+            config.fast_render && matches!(config.plot_type, PlotType::Line | PlotType::Dot);
+        let reference = self.reference_overlay(target.get_dimensions(), config);
+        let paths = self.placed_paths(config);
+        Self::draw_layers(
+            &mut mesh,
+            &points,
+            overlays,
+            reference.as_ref(),
+            &paths,
+            config,
+            !fast_render,
+        );
+
+        self.draw_mesh(target, &mesh, config);
+        if !fast_render {
+            return (mesh, vec![]);
+        }
+        self.draw_primitives(target, &points, config);
+        (mesh, points)
+    }
+
+    /// Returns the positions of the vertices on the plot area.
+    fn points(vertices: &[Vertex]) -> Vec<Point> {
+        vertices
+            .iter()
+            .map(|x| point(x.position[0], x.position[1]))
+            .collect()
+    }
+
+    /// Tessellates the data, unless it's drawn separately, the overlays and
+    /// the reference curve, if any, stacked in the figure's layer order, with
+    /// the custom paths above them all.
+    fn draw_layers(
+        mesh: &mut VertexBuffers<Vertex, u32>,
+        points: &[Point],
+        overlays: &[Overlay],
+        reference: Option<&Overlay>,
+        paths: &[(lyon::path::Path, [u8; 3], PathStyle)],
+        config: &FigureConfig,
+        include_data: bool,
+    ) {
+        let reference = reference.map(std::slice::from_ref).unwrap_or(&[]);
+        let default_order = [Layer::Overlays, Layer::Data, Layer::Reference];
+        let order = config.layer_order.as_deref().unwrap_or(&default_order);
+
+        // With the depth test, the first shape drawn wins where shapes at the
+        // same depth overlap, so the top layer is tessellated first. Without
+        // it, the last shape drawn wins.
+        let layers: Box<dyn Iterator<Item = &Layer>> = if config.disable_depth_test {
+            Box::new(order.iter())
+        } else {
+            Self::draw_paths(mesh, paths, config);
+            Box::new(order.iter().rev())
+        };
+        for layer in layers {
+            match layer {
+                Layer::Overlays => Self::draw_overlays(mesh, overlays, config),
+                Layer::Data if include_data => Self::draw_data(mesh, points, config),
+                Layer::Data => (),
+                Layer::Reference => Self::draw_overlays(mesh, reference, config),
+            }
+        }
+        if config.disable_depth_test {
+            Self::draw_paths(mesh, paths, config);
+        }
+    }
+
+    /// Tessellates custom paths that have already been mapped onto the plot
+    /// area.
+    fn draw_paths(
+        mesh: &mut VertexBuffers<Vertex, u32>,
+        paths: &[(lyon::path::Path, [u8; 3], PathStyle)],
+        config: &FigureConfig,
+    ) {
+        for (path, color, style) in paths {
+            let ctor = VertexCtor((*color).into(), ZDepth::Near);
+            match style {
+                PathStyle::Stroke => StrokeTessellator::new()
+                    .tessellate_path(
+                        path.iter(),
+                        &Self::line_options(config),
+                        &mut BuffersBuilder::new(mesh, ctor),
+                    )
+                    .expect("Could not draw path"),
+                PathStyle::Fill => FillTessellator::new()
+                    .tessellate_path(
+                        path.iter(),
+                        &FillOptions::tolerance(Self::tolerance(config)),
+                        &mut BuffersBuilder::new(mesh, ctor),
+                    )
+                    .expect("Could not draw path"),
+            };
+        }
+    }
+
+    /// Tessellates each overlay as a line in its own color.
+    fn draw_overlays(
+        mesh: &mut VertexBuffers<Vertex, u32>,
+        overlays: &[Overlay],
+        config: &FigureConfig,
+    ) {
+        for overlay in overlays {
+            stroke_polyline(
+                Self::points(&overlay.vertices).into_iter(),
+                false,
+                &Self::line_options(config),
+                &mut BuffersBuilder::new(mesh, VertexCtor(overlay.color, ZDepth::Near)),
+            )
+            .expect("Could not draw overlay");
+        }
+    }
+
+    /// Draws everything behind the data: the labels and grid, from the cache
+    /// if it's enabled, and the frame time.
+    fn draw_frame_background<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        if config.cache_grid {
+            self.draw_cached_background(target, config);
+        } else {
+            self.draw_background(target, config);
+        }
+        if config.show_fps {
+            self.draw_fps(target, config);
+        }
+        if self.status.is_some() {
+            self.draw_status(target, config);
+        }
+        if self.stats.is_some() {
+            self.draw_stats(target, config);
+        }
+        if let Some([[x0, y0], [x1, y1]]) = self.selection {
+            let mut mesh: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+            stroke_quad(
+                point(x0, y0),
+                point(x0, y1),
+                point(x1, y1),
+                point(x1, y0),
+                &StrokeOptions::tolerance(Self::tolerance(config)).with_line_width(0.002),
+                &mut BuffersBuilder::new(&mut mesh, VertexCtor([0, 0, 0].into(), ZDepth::Near)),
+            )
+            .expect("Could not draw selection");
+            self.draw_mesh(target, &mesh, config);
+        }
+        if !self.events.is_empty() {
+            self.draw_events(target, config);
+        }
+    }
+
+    /// Clears the target and draws the labels and grid onto it.
+    fn draw_background<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let [r, g, b] = BACKGROUND.map(|c| f32::from(c) / 255.0);
+        target.clear_color_and_depth((r, g, b, 1.0), 1.0);
+        let mut mesh: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        self.draw_text(target, config);
+        self.draw_grid(&mut mesh, target.get_dimensions(), config);
+        self.draw_mesh(target, &mesh, config);
+    }
+
+    /// Copies the labels and grid onto the target from the cache, redrawing
+    /// the cache first if the limits or size of the target have changed since
+    /// it was drawn. The cache isn't multisampled, so the grid may look
+    /// slightly rougher than when drawn directly.
+    fn draw_cached_background<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let key = GridKey {
+            dimensions: target.get_dimensions(),
+            xlim: config.xlim,
+            ylim: config.ylim,
+            xlabel: config.xlabel.as_ref().map(|label| label.to_string()),
+            ylabel: config.ylabel.as_ref().map(|label| label.to_string()),
+            categories: config
+                .categories
+                .as_ref()
+                .map(|categories| categories.iter().map(|c| c.to_string()).collect()),
+            y_ticks: config.y_ticks.clone(),
+            grid_spacing: config.grid_spacing,
+        };
+        let stale = match &self.grid_cache {
+            Some((cached, _)) => *cached != key,
+            None => true,
+        };
+        if stale {
+            let (width, height) = key.dimensions;
+            let texture = glium::texture::Texture2d::empty_with_format(
+                &self.display,
+                glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                glium::texture::MipmapsOption::NoMipmap,
+                width,
+                height,
+            )
+            .expect("Could not create grid cache");
+            let depth = glium::framebuffer::DepthRenderBuffer::new(
+                &self.display,
+                glium::texture::DepthFormat::I24,
+                width,
+                height,
+            )
+            .expect("Could not create grid cache depth buffer");
+            let mut cache = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(
+                &self.display,
+                &texture,
+                &depth,
+            )
+            .expect("Could not create grid cache framebuffer");
+            self.draw_background(&mut cache, config);
+            drop(cache);
+            self.grid_cache = Some((key, texture));
+        }
+        if let Some((_, texture)) = &self.grid_cache {
+            texture
+                .as_surface()
+                .fill(target, glium::uniforms::MagnifySamplerFilter::Nearest);
+        }
+
+        // The data always goes on top of the cached background.
+        target.clear_depth(1.0);
+    }
+
+    /// Returns the parameters to draw onto a target of the given size with.
+    /// Without the depth test, shapes are layered purely in the order they're
+    /// drawn.
+    fn draw_parameters(
+        &self,
+        dimensions: (u32, u32),
+        config: &FigureConfig,
+    ) -> glium::DrawParameters<'a> {
+        let draw_parameters = glium::DrawParameters {
+            viewport: Self::viewport(dimensions, config),
+            ..self.draw_parameters.clone()
+        };
+        if config.disable_depth_test {
+            glium::DrawParameters {
+                depth: glium::Depth::default(),
+                ..draw_parameters
+            }
+        } else {
+            draw_parameters
+        }
+    }
+
+    /// Draws a tessellated mesh onto the target.
+    fn draw_mesh<S>(&self, target: &mut S, mesh: &VertexBuffers<Vertex, u32>, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let dimensions = target.get_dimensions();
+        let ortho_mat = Self::viewport_projection(dimensions, config);
+        let ortho: &[[f32; 4]; 4] = ortho_mat.as_ref();
+        let uniforms = uniform! {
+            projection: *ortho,
+        };
+
+        let vertex_buffer = glium::VertexBuffer::new(&self.display, &mesh.vertices)
+            .expect("Could not create vertex buffer");
+        let indices = glium::IndexBuffer::new(
+            &self.display,
+            glium::index::PrimitiveType::TrianglesList,
+            &mesh.indices,
+        )
+        .expect("Could not create index buffer");
+
+        target
+            .draw(
+                &vertex_buffer,
+                &indices,
+                &self.program,
+                &uniforms,
+                &self.draw_parameters(dimensions, config),
+            )
+            .expect("Could not draw the frame");
+    }
+
+    /// Draws Line and Dot plots directly as GL lines and points, skipping
+    /// tessellation. Much cheaper for large numbers of points, but lines and
+    /// dots are drawn at a fixed size in pixels.
+    fn draw_primitives<S>(&self, target: &mut S, points: &[Point], config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        // Each segment of a line is drawn as a strip over its own run of the
+        // vertex buffer, so a point is only uploaded more than once where
+        // the line changes color.
+        let (vertices, runs, primitive) = match config.plot_type {
+            PlotType::Line if points.len() > 1 => {
+                let mut vertices = vec![];
+                let mut runs = vec![];
+                for (segment, color) in Self::segments(points, config) {
+                    let start = vertices.len();
+                    vertices.extend(
+                        segment
+                            .iter()
+                            .map(|pt| Vertex::with_depth(pt.x, pt.y, color, ZDepth::Near)),
+                    );
+                    runs.push(start..vertices.len());
+                }
+                (vertices, runs, glium::index::PrimitiveType::LineStrip)
+            }
+            _ => {
+                let vertices: Vec<Vertex> = points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pt)| {
+                        let color = Self::point_color(pt, i, points.len(), config);
+                        Vertex::with_depth(pt.x, pt.y, color, ZDepth::Near)
+                    })
+                    .collect();
+                let runs = std::iter::once(0..vertices.len()).collect();
+                (vertices, runs, glium::index::PrimitiveType::Points)
+            }
+        };
+
+        let dimensions = target.get_dimensions();
+        let ortho_mat = Self::viewport_projection(dimensions, config);
+        let ortho: &[[f32; 4]; 4] = ortho_mat.as_ref();
+        let vertex_buffer = glium::VertexBuffer::new(&self.display, &vertices)
+            .expect("Could not create vertex buffer");
+        let uniforms = uniform! {
+            projection: *ortho,
+        };
+        let draw_parameters = glium::DrawParameters {
+            point_size: Some(4.0),
+            ..self.draw_parameters(dimensions, config)
+        };
+        for run in runs {
+            let vertices = vertex_buffer
+                .slice(run)
+                .expect("Could not slice vertex buffer");
+            target
+                .draw(
+                    vertices,
+                    glium::index::NoIndices(primitive),
+                    &self.program,
+                    &uniforms,
+                    &draw_parameters,
+                )
+                .expect("Could not draw the frame");
+        }
+    }
+
+    /// Returns the square region of a target of the given size that meshes
+    /// are drawn into if `square_plot` is set, centered with blank margins
+    /// along the longer side of the target. Returns None if the whole target
+    /// is drawn into.
+    fn viewport((w, h): (u32, u32), config: &FigureConfig) -> Option<glium::Rect> {
+        if !config.square_plot || w == h {
+            return None;
+        }
+        let side = w.min(h);
+        Some(glium::Rect {
+            left: (w - side) / 2,
+            bottom: (h - side) / 2,
+            width: side,
+            height: side,
+        })
+    }
+
+    /// Returns the projection from plot coordinates to the viewport of a
+    /// target of the given size, which meshes are drawn with.
+    fn viewport_projection(dimensions: (u32, u32), config: &FigureConfig) -> cgmath::Matrix4<f32> {
+        match Self::viewport(dimensions, config) {
+            Some(_) => cgmath::ortho(-1.0, 1.0, -1.0, 1.0, -1.0, 1.0),
+            None => Self::projection(dimensions, config),
+        }
+    }
+
+    /// Returns the projection from plot coordinates to the whole of a target
+    /// of the given size, which text is drawn with. If `square_plot` is set,
+    /// the plot is kept square by adding blank margins along the longer side
+    /// of the target, lining up with the viewport meshes are drawn into.
+    /// Otherwise, the plot always fills the height of the target.
+    fn projection((w, h): (u32, u32), config: &FigureConfig) -> cgmath::Matrix4<f32> {
+        let aspect = w as f32 / h as f32;
+        if config.square_plot && aspect < 1.0 {
+            cgmath::ortho(-1.0, 1.0, -1.0 / aspect, 1.0 / aspect, -1.0, 1.0)
+        } else {
+            cgmath::ortho(-aspect, aspect, -1.0, 1.0, -1.0, 1.0)
+        }
+    }
+
+    /// Tessellates the data points into the mesh according to the plot type.
+    fn draw_data(mesh: &mut VertexBuffers<Vertex, u32>, points: &[Point], config: &FigureConfig) {
+        match config.plot_type {
+            // A line needs two points, so a lone point is drawn as a dot
+            // rather than not at all.
+            PlotType::Line if points.len() == 1 => Self::draw_dots(mesh, points, config),
+            PlotType::Line => {
+                Self::draw_line(mesh, points, config);
+                if let Some(every) = config.marker_every.filter(|&every| every > 0) {
+                    let markers: Vec<Point> = points.iter().step_by(every).cloned().collect();
+                    Self::draw_dots(mesh, &markers, config);
+                }
+            }
+            PlotType::Dot => Self::draw_dots(mesh, points, config),
+            PlotType::LineDot => {
+                Self::draw_line(mesh, points, config);
+                Self::draw_dots(mesh, points, config);
+            }
+            PlotType::Stem => {
+                let baseline = Self::baseline(config);
+                for (i, point) in points.iter().enumerate() {
+                    let color = Self::point_color(point, i, points.len(), config);
+                    stroke_polyline(
+                        [lyon::math::point(point.x, baseline), *point]
+                            .iter()
+                            .cloned(),
+                        false,
+                        &Self::line_options(config),
+                        &mut BuffersBuilder::new(mesh, VertexCtor(color, ZDepth::Near)),
+                    )
+                    .expect("Could not draw stem plot");
+                    Self::draw_marker(mesh, point, color, config);
+                }
+            }
+            PlotType::Bar => {
+                let baseline = Self::baseline(config);
+                let width = 1.6 * config.layout.plot_extent / points.len().max(1) as f32;
+                for (i, point) in points.iter().enumerate() {
+                    fill_rectangle(
+                        &rect(
+                            point.x - width / 2.0,
+                            point.y.min(baseline),
+                            width,
+                            (point.y - baseline).abs(),
+                        ),
+                        &FillOptions::tolerance(Self::tolerance(config)),
+                        &mut BuffersBuilder::new(
+                            mesh,
+                            VertexCtor(
+                                Self::point_color(point, i, points.len(), config),
+                                ZDepth::Near,
+                            ),
+                        ),
+                    )
+                    .expect("Could not draw bar plot");
+                }
+            }
+            PlotType::Area => {
+                let baseline = Self::baseline(config);
+                for (segment, color) in Self::segments(points, config) {
+                    if let (Some(first), Some(last)) = (segment.first(), segment.last()) {
+                        let outline = std::iter::once(lyon::math::point(first.x, baseline))
+                            .chain(segment.iter().cloned())
+                            .chain(std::iter::once(lyon::math::point(last.x, baseline)));
+                        fill_polyline(
+                            outline,
+                            &mut FillTessellator::new(),
+                            &FillOptions::tolerance(Self::tolerance(config)),
+                            &mut BuffersBuilder::new(mesh, VertexCtor(color, ZDepth::Near)),
+                        )
+                        .expect("Could not draw area plot");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Tessellates a line connecting the points in order.
+    fn draw_line(mesh: &mut VertexBuffers<Vertex, u32>, points: &[Point], config: &FigureConfig) {
+        for (segment, color) in Self::segments(points, config) {
+            stroke_polyline(
+                segment.iter().cloned(),
+                false,
+                &Self::line_options(config),
+                &mut BuffersBuilder::new(mesh, VertexCtor(color, ZDepth::Near)),
+            )
+            .expect("Could not draw line plot");
+        }
+    }
+
+    /// Tessellates a dot at each point.
+    fn draw_dots(mesh: &mut VertexBuffers<Vertex, u32>, points: &[Point], config: &FigureConfig) {
+        for (i, point) in points.iter().enumerate() {
+            let color = Self::point_color(point, i, points.len(), config);
+            Self::draw_marker(mesh, point, color, config);
+        }
+    }
+
+    /// Tessellates a single marker centered on the point in the figure's
+    /// marker shape and size.
+    fn draw_marker(
+        mesh: &mut VertexBuffers<Vertex, u32>,
+        point: &Point,
+        color: Color,
+        config: &FigureConfig,
+    ) {
+        let size = config.marker_size.unwrap_or(0.01);
+        let options = FillOptions::tolerance(Self::marker_tolerance(config));
+        let mut builder = BuffersBuilder::new(mesh, VertexCtor(color, ZDepth::Near));
+        match config.marker_shape {
+            MarkerShape::Circle => {
+                fill_circle(*point, size, &options, &mut builder).expect("Could not draw marker");
+            }
+            MarkerShape::Square => {
+                fill_rectangle(
+                    &rect(point.x - size, point.y - size, 2.0 * size, 2.0 * size),
+                    &options,
+                    &mut builder,
+                )
+                .expect("Could not draw marker");
+            }
+            MarkerShape::Diamond => {
+                fill_polyline(
+                    [
+                        lyon::math::point(point.x, point.y + size),
+                        lyon::math::point(point.x + size, point.y),
+                        lyon::math::point(point.x, point.y - size),
+                        lyon::math::point(point.x - size, point.y),
+                    ]
+                    .iter()
+                    .cloned(),
+                    &mut FillTessellator::new(),
+                    &options,
+                    &mut builder,
+                )
+                .expect("Could not draw marker");
+            }
+        }
+    }
+
+    /// Returns the color threshold of the figure mapped onto the plot area
+    /// along with the colors to use below and above it, if one is set.
+    fn threshold(config: &FigureConfig) -> Option<(f32, [u8; 3], [u8; 3])> {
+        let threshold = config.color_threshold?;
+        let ylim = config.ylim?;
+        let level = utils::normalize_value(threshold.level, ylim, config.layout.plot_extent);
+        Some((level, threshold.below, threshold.above))
+    }
+
+    /// Returns where a point falls along the colormap, given its index among
+    /// `count` points.
+    fn colormap_position(index: usize, count: usize) -> f32 {
+        if count > 1 {
+            index as f32 / (count - 1) as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the color a single point should be drawn with, given its
+    /// index among `count` points.
+    /// Every color keeps the opacity of the figure's color.
+    fn point_color(point: &Point, index: usize, count: usize, config: &FigureConfig) -> Color {
+        if let Some(color) = config
+            .point_colors
+            .as_ref()
+            .and_then(|colors| colors.get(index))
+        {
+            return config.color.with_rgb(*color);
+        }
+        if let Some(cmap) = config.colormap {
+            return config
+                .color
+                .with_rgb(cmap.sample(Self::colormap_position(index, count)));
+        }
+        let color = match Self::threshold(config) {
+            Some((level, below, above)) => {
+                if point.y >= level {
+                    above
+                } else {
+                    below
+                }
+            }
+            None => config.color.rgb,
+        };
+        if config.recency_shading {
+            config
+                .color
+                .with_rgb(Self::shade_recency(color, index, count))
+        } else {
+            config.color.with_rgb(color)
+        }
+    }
+
+    /// Fades a color towards the background by how old the point is, given
+    /// its index among `count` points from oldest to newest. The oldest point
+    /// keeps a quarter of its color so it stays visible.
+    fn shade_recency(color: [u8; 3], index: usize, count: usize) -> [u8; 3] {
+        let weight = 0.25 + 0.75 * Self::colormap_position(index, count);
+        let mut shaded = color;
+        for (c, bg) in shaded.iter_mut().zip(BACKGROUND.iter()) {
+            *c = (f32::from(*bg) + weight * (f32::from(*c) - f32::from(*bg))).round() as u8;
+        }
+        shaded
+    }
+
+    /// Splits a line into segments that each share a single color. Without a
+    /// color threshold, this is the whole line in the figure's color.
+    /// Otherwise, the line is split wherever it crosses the threshold. With a
+    /// colormap, each pair of neighbouring points is its own segment, colored
+    /// by where it starts along the colormap.
+    fn segments(points: &[Point], config: &FigureConfig) -> Vec<(Vec<Point>, Color)> {
+        if config.point_colors.is_some() || config.recency_shading {
+            return points
+                .windows(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    let color = Self::point_color(&pair[0], i, points.len(), config);
+                    (pair.to_vec(), color)
+                })
+                .collect();
+        }
+        if let Some(cmap) = config.colormap {
+            return points
+                .windows(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    let position = Self::colormap_position(i, points.len());
+                    (pair.to_vec(), config.color.with_rgb(cmap.sample(position)))
+                })
+                .collect();
+        }
+        let (level, below, above) = match Self::threshold(config) {
+            Some(threshold) => threshold,
+            None => return vec![(points.to_vec(), config.color)],
+        };
+        let color = |pt: &Point| {
+            let rgb = if pt.y >= level { above } else { below };
+            config.color.with_rgb(rgb)
+        };
+
+        let mut segments = vec![];
+        let mut segment: Vec<Point> = vec![];
+        for pt in points {
+            if let Some(prev) = segment.last().cloned() {
+                if color(&prev) != color(pt) {
+                    // Close off the current segment where the line crosses
+                    // the threshold and start the next one from there.
+                    let t = (level - prev.y) / (pt.y - prev.y);
+                    let crossing = lyon::math::point(prev.x + t * (pt.x - prev.x), level);
+                    segment.push(crossing);
+                    segments.push((segment, color(&prev)));
+                    segment = vec![crossing];
+                }
+            }
+            segment.push(*pt);
+        }
+        if let Some(last) = segment.last().cloned() {
+            segments.push((segment, color(&last)));
+        }
+        segments
+    }
+
+    /// Returns the tolerance to tessellate shapes with, in plot coordinates.
+    fn tolerance(config: &FigureConfig) -> f32 {
+        config.tessellation_tolerance.unwrap_or(0.01)
+    }
+
+    /// Returns the options to stroke the data and overlays with.
+    fn line_options(config: &FigureConfig) -> StrokeOptions {
+        let cap = match config.line_cap {
+            figure::LineCap::Butt => LineCap::Butt,
+            figure::LineCap::Round => LineCap::Round,
+            figure::LineCap::Square => LineCap::Square,
+        };
+        let join = match config.line_join {
+            figure::LineJoin::Miter => LineJoin::Miter,
+            figure::LineJoin::Round => LineJoin::Round,
+            figure::LineJoin::Bevel => LineJoin::Bevel,
+        };
+        StrokeOptions::tolerance(Self::tolerance(config))
+            .with_line_width(0.002)
+            .with_line_cap(cap)
+            .with_line_join(join)
+    }
+
+    /// Returns the tolerance to tessellate markers with, in plot coordinates.
+    fn marker_tolerance(config: &FigureConfig) -> f32 {
+        config
+            .marker_tolerance
+            .unwrap_or_else(|| Self::tolerance(config))
+    }
+
+    /// Returns the baseline of the figure mapped onto the plot area, clamped
+    /// to the edges of the plot.
+    fn baseline(config: &FigureConfig) -> f32 {
+        let extent = config.layout.plot_extent;
+        match config.ylim {
+            Some(ylim) => {
+                utils::normalize_value(config.baseline, ylim, extent).clamp(-extent, extent)
+            }
+            None => -extent,
+        }
+    }
+
+    fn draw_text<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let ortho_mat = Self::projection(target.get_dimensions(), config);
+        let layout = config.layout;
+        let (x_target, y_target) = Self::tick_targets(target.get_dimensions(), config);
+        if let Some(text) = &config.xlabel {
+            let label = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
+            let text_width = label.get_width() * 0.1;
+            #[rustfmt::skip]
+            let matrix = ortho_mat * cgmath::Matrix4::new(
+                0.1, 0.0, 0.0, 0.0,
+                0.0, 0.1, 0.0, 0.0,
+                0.0, 0.0, 0.1, 0.0,
+                -text_width / 2.0, layout.xlabel_offset, 0.0, 1.0,
+            );
+            glium_text::draw(
+                &label,
+                &self.text_system,
+                target,
+                matrix,
+                (0.0, 0.0, 0.0, 1.0),
+            )
+            .expect("Could not draw x label");
+        }
+        if let Some(text) = &config.ylabel {
+            let label = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
+            let text_width = label.get_width() * 0.1;
+            #[rustfmt::skip]
+            let matrix = ortho_mat * cgmath::Matrix4::new(
+                0.1, 0.0, 0.0, 0.0,
+                0.0, 0.1, 0.0, 0.0,
+                0.0, 1.0, 0.1, 0.0,
+                layout.ylabel_offset, -text_width / 2.0, 0.0, 1.0,
+            ) * cgmath::Matrix4::from_angle_z(cgmath::Deg(90.0));
+            glium_text::draw(
+                &label,
+                &self.text_system,
+                target,
+                matrix,
+                (0.0, 0.0, 0.0, 1.0),
+            )
+            .expect("Could not draw y label");
+        }
+        if let Some(categories) = &config.categories {
+            let count = categories.len();
+            for (i, category) in categories.iter().enumerate() {
+                let coord = layout.plot_extent * (2.0 * (i as f32 + 0.5) / count as f32 - 1.0);
+                let category_str =
+                    glium_text::TextDisplay::new(&self.text_system, &self.font, category);
+                let text_width = category_str.get_width() * 0.05;
+                #[rustfmt::skip]
+                let matrix = ortho_mat * cgmath::Matrix4::new(
+                    0.05, 0.0, 0.0, 0.0,
+                    0.0, 0.05, 0.0, 0.0,
+                    0.0, 0.0, 0.05, 0.0,
+                    coord - text_width / 2.0, layout.xtick_offset, 0.0, 1.0,
+                );
+                glium_text::draw(
+                    &category_str,
+                    &self.text_system,
+                    target,
+                    matrix,
+                    (0.0, 0.0, 0.0, 1.0),
+                )
+                .expect("Could not draw x axis categories");
+            }
+        } else if let Some(xlim) = config.xlim {
+            let ticks = Self::x_ticks(xlim, x_target, config);
+            let stepped = Self::stepped(x_target, Self::x_spacing(config));
+            for (coord, label) in Self::tick_labels(&ticks, stepped, config) {
+                let tick_str = glium_text::TextDisplay::new(&self.text_system, &self.font, &label);
+                let text_width = tick_str.get_width() * 0.05;
+                #[rustfmt::skip]
+                let matrix = ortho_mat * cgmath::Matrix4::new(
+                    0.05, 0.0, 0.0, 0.0,
+                    0.0, 0.05, 0.0, 0.0,
+                    0.0, 0.0, 0.05, 0.0,
+                    coord - text_width / 2.0, layout.xtick_offset, 0.0, 1.0,
+                );
+                glium_text::draw(
+                    &tick_str,
+                    &self.text_system,
+                    target,
+                    matrix,
+                    (0.0, 0.0, 0.0, 1.0),
+                )
+                .expect("Could not draw x axis values");
+            }
+        }
+        if let Some(ylim) = config.ylim {
+            let ticks = Self::y_ticks(ylim, y_target, config);
+            let stepped = Self::stepped(y_target, Self::y_spacing(config));
+            for (coord, label) in Self::tick_labels(&ticks, stepped, config) {
+                let tick_str = glium_text::TextDisplay::new(&self.text_system, &self.font, &label);
+                let text_height = tick_str.get_height() * 0.05;
+                #[rustfmt::skip]
+                let matrix = ortho_mat * cgmath::Matrix4::new(
+                    0.05, 0.0, 0.0, 0.0,
+                    0.0, 0.05, 0.0, 0.0,
+                    0.0, 0.0, 0.05, 0.0,
+                    layout.ytick_offset, coord - text_height / 2.0, 0.0, 1.0,
+                );
+                glium_text::draw(
+                    &tick_str,
+                    &self.text_system,
+                    target,
+                    matrix,
+                    (0.0, 0.0, 0.0, 1.0),
+                )
+                .expect("Could not draw y axis labels");
+            }
+        }
+        if let (Some(xlim), Some(ylim)) = (config.xlim, config.ylim) {
+            let extent = layout.plot_extent;
+            for &radius in &config.reference_circles {
+                // Label each circle where it crosses the 45 degree line, as
+                // long as that's inside the plot.
+                let offset = radius * std::f32::consts::FRAC_1_SQRT_2;
+                let x = utils::normalize_value(offset, xlim, extent);
+                let y = utils::normalize_value(offset, ylim, extent);
+                if x.abs() > extent || y.abs() > extent {
+                    continue;
+                }
+                let radius_str = glium_text::TextDisplay::new(
+                    &self.text_system,
+                    &self.font,
+                    &format!("{}", radius),
+                );
+                #[rustfmt::skip]
+                let matrix = ortho_mat * cgmath::Matrix4::new(
+                    0.04, 0.0, 0.0, 0.0,
+                    0.0, 0.04, 0.0, 0.0,
+                    0.0, 0.0, 0.04, 0.0,
+                    x + 0.01, y + 0.01, 0.0, 1.0,
+                );
+                glium_text::draw(
+                    &radius_str,
+                    &self.text_system,
+                    target,
+                    matrix,
+                    (0.2, 0.2, 0.2, 1.0),
+                )
+                .expect("Could not draw reference circle labels");
+            }
+        }
+        if let Some(text) = config.watermark {
+            let watermark = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
+            let text_width = watermark.get_width() * 0.04;
+            #[rustfmt::skip]
+            let matrix = ortho_mat * cgmath::Matrix4::new(
+                0.04, 0.0, 0.0, 0.0,
+                0.0, 0.04, 0.0, 0.0,
+                0.0, 0.0, 0.04, 0.0,
+                layout.plot_extent - text_width, -0.97, 0.0, 1.0,
+            );
+            glium_text::draw(
+                &watermark,
+                &self.text_system,
+                target,
+                matrix,
+                (0.0, 0.0, 0.0, 0.3),
+            )
+            .expect("Could not draw watermark");
+        }
+    }
+
+    /// Draws the time taken by the last frame above the plot.
+    fn draw_fps<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let ortho_mat = Self::projection(target.get_dimensions(), config);
+        let layout = config.layout;
+        let secs = self.last_frame_time.as_secs_f32();
+        let fps = if secs > 0.0 { 1.0 / secs } else { 0.0 };
+        let frame_str = glium_text::TextDisplay::new(
+            &self.text_system,
+            &self.font,
+            &format!("{:.1} ms ({:.0} fps)", secs * 1000.0, fps),
+        );
+        #[rustfmt::skip]
+        let matrix = ortho_mat * cgmath::Matrix4::new(
+            0.05, 0.0, 0.0, 0.0,
+            0.0, 0.05, 0.0, 0.0,
+            0.0, 0.0, 0.05, 0.0,
+            -layout.plot_extent, layout.plot_extent + 0.05, 0.0, 1.0,
+        );
+        glium_text::draw(
+            &frame_str,
+            &self.text_system,
+            target,
+            matrix,
+            (0.0, 0.0, 0.0, 1.0),
+        )
+        .expect("Could not draw frame time");
+    }
+
+    /// Draws the status message above the top-right corner of the plot.
+    fn draw_status<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let text = match &self.status {
+            Some(text) => text,
+            None => return,
+        };
+        let ortho_mat = Self::projection(target.get_dimensions(), config);
+        let layout = config.layout;
+        let status_str = glium_text::TextDisplay::new(&self.text_system, &self.font, text);
+        let text_width = status_str.get_width() * 0.05;
+        #[rustfmt::skip]
+        let matrix = ortho_mat * cgmath::Matrix4::new(
+            0.05, 0.0, 0.0, 0.0,
+            0.0, 0.05, 0.0, 0.0,
+            0.0, 0.0, 0.05, 0.0,
+            layout.plot_extent - text_width, layout.plot_extent + 0.05, 0.0, 1.0,
+        );
+        glium_text::draw(
+            &status_str,
+            &self.text_system,
+            target,
+            matrix,
+            (0.0, 0.0, 0.0, 1.0),
+        )
+        .expect("Could not draw status");
+    }
+
+    /// Draws a short tick along the bottom of the plot at each event within
+    /// the x limits. Labels are only drawn where they won't crowd the label
+    /// before them.
+    fn draw_events<S>(&self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let xlim = match config.xlim {
+            Some(xlim) => xlim,
+            None => return,
+        };
+        let ortho_mat = Self::projection(target.get_dimensions(), config);
+        let extent = config.layout.plot_extent;
+        let (min, max) = (xlim[0].min(xlim[1]), xlim[0].max(xlim[1]));
+        let mut mesh: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        let mut last_label: Option<f32> = None;
+        for (time, label) in &self.events {
+            if *time < min || *time > max {
+                continue;
+            }
+            let x = utils::normalize_value(*time, xlim, extent);
+            fill_rectangle(
+                &rect(x - 0.001, -extent, 0.002, 0.04),
+                &FillOptions::tolerance(Self::tolerance(config)),
+                &mut BuffersBuilder::new(&mut mesh, VertexCtor([0, 0, 0].into(), ZDepth::Near)),
+            )
+            .expect("Could not draw event");
+
+            let label = match label {
+                Some(label) => label,
+                None => continue,
+            };
+            let crowded = match last_label {
+                Some(last) => (x - last).abs() < 0.15,
+                None => false,
+            };
+            if crowded {
+                continue;
+            }
+            last_label = Some(x);
+            let label_str = glium_text::TextDisplay::new(&self.text_system, &self.font, label);
+            #[rustfmt::skip]
+            let matrix = ortho_mat * cgmath::Matrix4::new(
+                0.04, 0.0, 0.0, 0.0,
+                0.0, 0.04, 0.0, 0.0,
+                0.0, 0.0, 0.04, 0.0,
+                x + 0.005, -extent + 0.045, 0.0, 1.0,
+            );
+            glium_text::draw(
+                &label_str,
+                &self.text_system,
+                target,
+                matrix,
+                (0.0, 0.0, 0.0, 1.0),
+            )
+            .expect("Could not draw event label");
+        }
+        self.draw_mesh(target, &mesh, config);
+    }
+
+    /// Draws the lines of statistics inside the chosen corner of the plot.
+    fn draw_stats<S>(&mut self, target: &mut S, config: &FigureConfig)
+    where
+        S: glium::Surface,
+    {
+        let stats = match &self.stats {
+            Some(stats) => stats,
+            None => return,
+        };
+        let ortho_mat = Self::projection(target.get_dimensions(), config);
+        let extent = config.layout.plot_extent;
+        let lines: Vec<glium_text::TextDisplay<_>> = stats
+            .iter()
+            .map(|line| glium_text::TextDisplay::new(&self.text_system, &self.font, line))
+            .collect();
+        let width = lines
+            .iter()
+            .map(|line| line.get_width() * 0.04)
+            .fold(0.0, f32::max);
+        let line_height = 0.05;
+        let x = match config.stats_corner {
+            Corner::TopLeft | Corner::BottomLeft => -extent + 0.02,
+            Corner::TopRight | Corner::BottomRight => extent - 0.02 - width,
+        };
+        let top = match config.stats_corner {
+            Corner::TopLeft | Corner::TopRight => extent - line_height,
+            Corner::BottomLeft | Corner::BottomRight => {
+                -extent + 0.02 + line_height * (lines.len() as f32 - 1.0)
+            }
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let y = top - line_height * i as f32;
+            #[rustfmt::skip]
+            let matrix = ortho_mat * cgmath::Matrix4::new(
+                0.04, 0.0, 0.0, 0.0,
+                0.0, 0.04, 0.0, 0.0,
+                0.0, 0.0, 0.04, 0.0,
+                x, y, 0.0, 1.0,
+            );
+            glium_text::draw(
+                line,
+                &self.text_system,
+                target,
+                matrix,
+                (0.0, 0.0, 0.0, 1.0),
+            )
+            .expect("Could not draw statistics");
+        }
+    }
+
+    /// Draws emphasized lines where x and y are zero, if zero falls within
+    /// the limits of the plot.
+    fn draw_zero_axis(mesh: &mut VertexBuffers<Vertex, u32>, config: &FigureConfig) {
+        let extent = config.layout.plot_extent;
+        let options = StrokeOptions::tolerance(Self::tolerance(config)).with_line_width(0.004);
+        if let Some(ylim) = config.ylim {
+            if ylim[0].min(ylim[1]) <= 0.0 && ylim[0].max(ylim[1]) >= 0.0 {
+                let y = utils::normalize_value(0.0, ylim, extent);
+                stroke_polyline(
+                    [point(-extent, y), point(extent, y)].iter().cloned(),
+                    false,
+                    &options,
+                    &mut BuffersBuilder::new(
+                        mesh,
+                        VertexCtor([0x20, 0x20, 0x20].into(), ZDepth::Far),
+                    ),
+                )
+                .expect("Could not draw zero axis");
+            }
+        }
+        if let Some(xlim) = config.xlim {
+            if xlim[0].min(xlim[1]) <= 0.0 && xlim[0].max(xlim[1]) >= 0.0 {
+                let x = utils::normalize_value(0.0, xlim, extent);
+                stroke_polyline(
+                    [point(x, -extent), point(x, extent)].iter().cloned(),
+                    false,
+                    &options,
+                    &mut BuffersBuilder::new(
+                        mesh,
+                        VertexCtor([0x20, 0x20, 0x20].into(), ZDepth::Far),
+                    ),
+                )
+                .expect("Could not draw zero axis");
+            }
+        }
+    }
+
+    /// Draws circles centered on the origin at each of the reference radii.
+    /// The circles are mapped through the axis limits, so they appear as
+    /// ellipses if the axes are scaled differently, and are cut off at the
+    /// edges of the plot.
+    fn draw_reference_circles(mesh: &mut VertexBuffers<Vertex, u32>, config: &FigureConfig) {
+        let (xlim, ylim) = match (config.xlim, config.ylim) {
+            (Some(xlim), Some(ylim)) => (xlim, ylim),
+            _ => return,
+        };
+        let extent = config.layout.plot_extent;
+        let options = StrokeOptions::tolerance(Self::tolerance(config)).with_line_width(0.002);
+        for &radius in &config.reference_circles {
+            // Split the circle into arcs wherever it leaves the plot.
+            let mut arcs: Vec<Vec<Point>> = vec![vec![]];
+            for angle in linspace(0.0, 2.0 * std::f32::consts::PI, 129) {
+                let x = utils::normalize_value(radius * angle.cos(), xlim, extent);
+                let y = utils::normalize_value(radius * angle.sin(), ylim, extent);
+                if x.abs() > extent || y.abs() > extent {
+                    arcs.push(vec![]);
+                } else if let Some(arc) = arcs.last_mut() {
+                    arc.push(point(x, y));
+                }
+            }
+            for arc in arcs.into_iter().filter(|arc| arc.len() > 1) {
+                stroke_polyline(
+                    arc.into_iter(),
+                    false,
+                    &options,
+                    &mut BuffersBuilder::new(
+                        mesh,
+                        VertexCtor([0x40, 0x40, 0x40].into(), ZDepth::Far),
+                    ),
+                )
+                .expect("Could not draw reference circles");
+            }
+        }
+    }
+
+    /// Returns the ticks along an axis as pairs of their position on the plot
+    /// area and their value in data coordinates. Without a tick target, the
+    /// axis is divided into `count` evenly spaced ticks.
+    fn ticks(lims: [f32; 2], count: usize, target: Option<usize>, extent: f32) -> Vec<(f32, f32)> {
+        match target {
+            Some(target) => utils::nice_ticks(lims[0], lims[1], target)
+                .into_iter()
+                .map(|tick| (utils::normalize_value(tick, lims, extent), tick))
+                .collect(),
+            None => linspace(-extent, extent, count)
+                .zip(linspace(lims[0], lims[1], count))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of nice ticks to aim for along the x and y axes on
+    /// a target of the given size. With a tick spacing set, this adapts to
+    /// the size of the plot on screen. Otherwise, it's the figure's tick
+    /// targets.
+    fn tick_targets(
+        dimensions: (u32, u32),
+        config: &FigureConfig,
+    ) -> (Option<usize>, Option<usize>) {
+        match config.tick_spacing {
+            Some(spacing) if spacing > 0.0 => {
+                let target =
+                    ((Self::plot_size(dimensions, config) / spacing).round() as usize).max(2);
+                (Some(target), Some(target))
+            }
+            _ => (config.x_tick_target, config.y_tick_target),
+        }
+    }
+
+    /// Returns the number of decimal places to label ticks with. Ticks
+    /// stepped at a round interval, either nice ticks or a fixed spacing, use
+    /// just enough places to tell neighbouring ticks apart.
+    fn tick_precision(ticks: &[(f32, f32)], stepped: bool) -> usize {
+        match (stepped, ticks) {
+            (true, [(_, first), (_, second), ..]) => {
+                (-(second - first).abs().log10().floor()).max(0.0) as usize
+            }
+            _ => 2,
+        }
+    }
+
+    /// Returns the position of each tick on the plot area along with its
+    /// label, written in the figure's number format.
+    fn tick_labels(
+        ticks: &[(f32, f32)],
+        stepped: bool,
+        config: &FigureConfig,
+    ) -> Vec<(f32, String)> {
+        let precision = Self::tick_precision(ticks, stepped);
+        let values: Vec<f32> = ticks.iter().map(|(_, tick)| *tick).collect();
+        ticks
+            .iter()
+            .map(|(coord, _)| *coord)
+            .zip(config.number_format.format_ticks(&values, precision))
+            .collect()
+    }
+
+    /// Returns the positions of the gridlines along an axis on the plot area.
+    fn grid_coords(
+        lims: Option<[f32; 2]>,
+        count: usize,
+        target: Option<usize>,
+        extent: f32,
+    ) -> Vec<f32> {
+        match lims {
+            Some(lims) => Self::ticks(lims, count, target, extent)
+                .into_iter()
+                .map(|(coord, _)| coord)
+                .collect(),
+            None => linspace(-extent, extent, count).collect(),
+        }
+    }
+
+    /// Returns the gridline spacing along the x axis in data units, if the
+    /// figure has a usable one.
+    fn x_spacing(config: &FigureConfig) -> Option<f32> {
+        config
+            .grid_spacing
+            .map(|[dx, _]| dx)
+            .filter(|dx| dx.is_finite() && *dx > 0.0)
+    }
+
+    /// Returns the gridline spacing along the y axis in data units, if the
+    /// figure has a usable one.
+    fn y_spacing(config: &FigureConfig) -> Option<f32> {
+        config
+            .grid_spacing
+            .map(|[_, dy]| dy)
+            .filter(|dy| dy.is_finite() && *dy > 0.0)
+    }
+
+    /// Returns whether ticks are stepped at a round interval, so their labels
+    /// only need enough places to tell the steps apart.
+    fn stepped(target: Option<usize>, spacing: Option<f32>) -> bool {
+        target.is_some() || spacing.is_some()
+    }
+
+    /// The most ticks drawn along an axis at a fixed spacing. A spacing too
+    /// fine for the limits falls back to the automatic ticks rather than
+    /// drawing a solid block of gridlines.
+    const MAX_SPACED_TICKS: i64 = 200;
+
+    /// Returns ticks at every multiple of the spacing within the limits, or
+    /// None if there would be too many of them.
+    fn spaced_ticks(lims: [f32; 2], spacing: f32, extent: f32) -> Option<Vec<(f32, f32)>> {
+        let (min, max) = (lims[0].min(lims[1]), lims[0].max(lims[1]));
+        let first = (min / spacing).ceil() as i64;
+        let last = (max / spacing).floor() as i64;
+        if last - first >= Self::MAX_SPACED_TICKS {
+            return None;
+        }
+        Some(
+            (first..=last)
+                .map(|i| {
+                    let tick = i as f32 * spacing;
+                    (utils::normalize_value(tick, lims, extent), tick)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the ticks along the x axis, at multiples of the figure's grid
+    /// spacing if it has one.
+    fn x_ticks(xlim: [f32; 2], target: Option<usize>, config: &FigureConfig) -> Vec<(f32, f32)> {
+        let extent = config.layout.plot_extent;
+        Self::x_spacing(config)
+            .and_then(|dx| Self::spaced_ticks(xlim, dx, extent))
+            .unwrap_or_else(|| Self::ticks(xlim, 6, target, extent))
+    }
+
+    /// Returns the positions of the gridlines along the x axis on the plot
+    /// area.
+    fn x_grid_coords(target: Option<usize>, config: &FigureConfig) -> Vec<f32> {
+        match config.xlim {
+            Some(xlim) => Self::x_ticks(xlim, target, config)
+                .into_iter()
+                .map(|(coord, _)| coord)
+                .collect(),
+            None => Self::grid_coords(None, 6, target, config.layout.plot_extent),
+        }
+    }
+
+    /// Returns the ticks along the y axis, at the figure's explicit y ticks
+    /// within the limits if it has any, or else at multiples of its grid
+    /// spacing if it has one.
+    fn y_ticks(ylim: [f32; 2], target: Option<usize>, config: &FigureConfig) -> Vec<(f32, f32)> {
+        let extent = config.layout.plot_extent;
+        match &config.y_ticks {
+            Some(ticks) => {
+                let (min, max) = (ylim[0].min(ylim[1]), ylim[0].max(ylim[1]));
+                ticks
+                    .iter()
+                    .filter(|&&tick| tick >= min && tick <= max)
+                    .map(|&tick| (utils::normalize_value(tick, ylim, extent), tick))
+                    .collect()
+            }
+            None => Self::y_spacing(config)
+                .and_then(|dy| Self::spaced_ticks(ylim, dy, extent))
+                .unwrap_or_else(|| Self::ticks(ylim, 5, target, extent)),
+        }
+    }
+
+    /// Returns the positions of the gridlines along the y axis on the plot
+    /// area.
+    fn y_grid_coords(target: Option<usize>, config: &FigureConfig) -> Vec<f32> {
+        match config.ylim {
+            Some(ylim) => Self::y_ticks(ylim, target, config)
+                .into_iter()
+                .map(|(coord, _)| coord)
+                .collect(),
+            None => Self::grid_coords(None, 5, target, config.layout.plot_extent),
+        }
+    }
+
+    /// Draws the zero axes and reference circles, which sit on top of the
+    /// gridlines.
+    fn draw_guides(mesh: &mut VertexBuffers<Vertex, u32>, config: &FigureConfig) {
+        if config.show_zero_axis {
+            Self::draw_zero_axis(mesh, config);
+        }
+        Self::draw_reference_circles(mesh, config);
+    }
+
+    fn draw_grid(
+        &mut self,
+        mesh: &mut VertexBuffers<Vertex, u32>,
+        dimensions: (u32, u32),
+        config: &FigureConfig,
+    ) {
+        let extent = config.layout.plot_extent;
+        let (x_target, y_target) = Self::tick_targets(dimensions, config);
+        let mut tessellator = FillTessellator::new();
+
+        // The zero axes share a depth with the gridlines, so they need to be
+        // tessellated first to stay visible where the two overlap. Without
+        // the depth test, the last shape drawn wins instead.
+        if !config.disable_depth_test {
+            Self::draw_guides(mesh, config);
+        }
+
+        for tick in Self::x_grid_coords(x_target, config) {
+            fill_polyline(
+                [
+                    point(tick - 0.001, extent),
+                    point(tick - 0.001, -extent),
+                    point(tick + 0.001, -extent),
+                    point(tick + 0.001, extent),
+                ]
+                .iter()
+                .cloned(),
+                &mut tessellator,
+                &FillOptions::tolerance(Self::tolerance(config)),
+                &mut BuffersBuilder::new(mesh, VertexCtor([0x5d, 0x5d, 0x5d].into(), ZDepth::Far)),
+            )
+            .expect("Could not draw grid");
+        }
+
+        for tick in Self::y_grid_coords(y_target, config) {
+            fill_polyline(
+                [
+                    point(extent, tick - 0.001),
+                    point(-extent, tick - 0.001),
+                    point(-extent, tick + 0.001),
+                    point(extent, tick + 0.001),
+                ]
+                .iter()
+                .cloned(),
+                &mut tessellator,
+                &FillOptions::tolerance(Self::tolerance(config)),
+                &mut BuffersBuilder::new(mesh, VertexCtor([0x5d, 0x5d, 0x5d].into(), ZDepth::Far)),
+            )
+            .expect("Could not draw grid");
+        }
+
+        if config.disable_depth_test {
+            Self::draw_guides(mesh, config);
+        }
+
+        stroke_quad(
+            point(-extent, -extent),
+            point(-extent, extent),
+            point(extent, extent),
+            point(extent, -extent),
+            &StrokeOptions::tolerance(Self::tolerance(config)).with_line_width(0.001),
+            &mut BuffersBuilder::new(mesh, VertexCtor([0, 0, 0].into(), ZDepth::Near)),
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A configuration with both limits set, so ticks can be computed.
+    fn config_with_limits<'a>() -> FigureConfig<'a> {
+        FigureConfig {
+            xlim: Some([-3.0, 17.0]),
+            ylim: Some([0.5, -2.5]),
+            ..Default::default()
+        }
+    }
+
+    /// Maps a point in plot coordinates through a projection onto a region of
+    /// pixels, given as its left, bottom, width and height.
+    fn to_pixels(projection: cgmath::Matrix4<f32>, [left, bottom, w, h]: [u32; 4]) -> [f32; 2] {
+        let ndc = projection * cgmath::Vector4::new(0.5, -0.25, 0.0, 1.0);
+        [
+            left as f32 + (ndc.x + 1.0) * w as f32 / 2.0,
+            bottom as f32 + (ndc.y + 1.0) * h as f32 / 2.0,
+        ]
+    }
+
+    #[test]
+    fn square_plot_viewport_is_centered_and_lines_up_with_text() {
+        let config = FigureConfig {
+            square_plot: true,
+            ..Default::default()
+        };
+        for (dimensions, [left, bottom]) in [((400, 200), [100, 0]), ((200, 300), [0, 50])] {
+            let viewport = Window::viewport(dimensions, &config).unwrap();
+            let side = dimensions.0.min(dimensions.1);
+            assert_eq!(
+                [
+                    viewport.left,
+                    viewport.bottom,
+                    viewport.width,
+                    viewport.height
+                ],
+                [left, bottom, side, side]
+            );
+            let mesh = to_pixels(
+                Window::viewport_projection(dimensions, &config),
+                [left, bottom, side, side],
+            );
+            let text = to_pixels(
+                Window::projection(dimensions, &config),
+                [0, 0, dimensions.0, dimensions.1],
+            );
+            assert!((mesh[0] - text[0]).abs() < 1e-3 && (mesh[1] - text[1]).abs() < 1e-3);
+        }
+        assert!(Window::viewport((300, 300), &config).is_none());
+        assert!(Window::viewport((400, 200), &FigureConfig::default()).is_none());
+    }
+
+    fn assert_grid_matches_ticks(config: &FigureConfig, target: Option<usize>) {
+        let x_ticks: Vec<f32> = Window::x_ticks(config.xlim.unwrap(), target, config)
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect();
+        let y_ticks: Vec<f32> = Window::y_ticks(config.ylim.unwrap(), target, config)
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect();
+        assert!(!x_ticks.is_empty() && !y_ticks.is_empty());
+        assert_eq!(Window::x_grid_coords(target, config), x_ticks);
+        assert_eq!(Window::y_grid_coords(target, config), y_ticks);
+    }
+
+    #[test]
+    fn grid_matches_default_ticks() {
+        let config = config_with_limits();
+        assert_grid_matches_ticks(&config, None);
+        assert_grid_matches_ticks(&config, Some(7));
+    }
+
+    #[test]
+    fn grid_matches_spaced_ticks() {
+        let config = FigureConfig {
+            grid_spacing: Some([2.5, 0.5]),
+            ..config_with_limits()
+        };
+        assert_grid_matches_ticks(&config, None);
+        let ticks = Window::x_ticks(config.xlim.unwrap(), None, &config);
+        assert_eq!(ticks.first().map(|(_, value)| *value), Some(-2.5));
+        assert_eq!(ticks.last().map(|(_, value)| *value), Some(15.0));
+    }
+
+    #[test]
+    fn grid_matches_explicit_y_ticks() {
+        let config = FigureConfig {
+            y_ticks: Some(vec![-2.0, -1.0, 0.0, 5.0]),
+            ..config_with_limits()
+        };
+        assert_grid_matches_ticks(&config, None);
+        let values: Vec<f32> = Window::y_ticks(config.ylim.unwrap(), None, &config)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(values, vec![-2.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn svg_includes_overlays() {
+        let overlay = Overlay {
+            vertices: vec![
+                Vertex::new(-0.5, 0.0, [0, 0, 0]),
+                Vertex::new(0.5, 0.0, [0, 0, 0]),
+            ],
+            color: [0x12, 0x34, 0x56].into(),
+        };
+        let svg = Window::render_svg((400, 400), &[], &[overlay], &config_with_limits());
+        assert!(svg.contains("stroke=\"#123456\""));
+    }
+
+    #[test]
+    fn data_vertices_take_alpha_from_the_color() {
+        let config = FigureConfig {
+            plot_type: PlotType::LineDot,
+            color: Color::rgba(0xff, 0, 0, 51),
+            ..Default::default()
+        };
+        let mut mesh = VertexBuffers::new();
+        let points = [point(0.0, 0.0), point(0.5, 0.5)];
+        Window::draw_data(&mut mesh, &points, &config);
+        assert!(!mesh.vertices.is_empty());
+        for vertex in &mesh.vertices {
+            assert_eq!(vertex.alpha(), 0.2);
+            assert_eq!(vertex.rgb(), [1.0, 0.0, 0.0]);
+        }
+        assert_eq!(Vertex::new(0.0, 0.0, [0, 0, 0]).alpha(), 1.0);
+    }
+
+    #[test]
+    fn kept_overlays_reuse_their_allocations() {
+        let overlay = |len: usize| Overlay {
+            vertices: vec![Vertex::new(0.0, 0.0, [0, 0, 0]); len],
+            color: [0, 0, 0].into(),
+        };
+        let mut kept = vec![overlay(100)];
+        let allocation = kept[0].vertices.as_ptr();
+        [overlay(10)][..].clone_into(&mut kept);
+        assert_eq!(kept[0].vertices.len(), 10);
+        assert_eq!(kept[0].vertices.as_ptr(), allocation);
+    }
+
+    #[test]
+    fn single_point_line_is_drawn() {
+        let config = FigureConfig {
+            plot_type: PlotType::Line,
+            ..Default::default()
+        };
+        let mut mesh = VertexBuffers::new();
+        Window::draw_data(&mut mesh, &[point(0.25, -0.5)], &config);
+        assert!(!mesh.vertices.is_empty());
+        assert!(!mesh.indices.is_empty());
     }
 }